@@ -1,3 +1,4 @@
 #[cfg(feature = "email-encryption")]
 pub mod email_encryption;
+pub mod mailgun;
 pub mod v1;
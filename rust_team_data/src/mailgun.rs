@@ -0,0 +1,120 @@
+//! This module describes how mailing lists are represented as Mailgun routes. It's shared between
+//! the team repository and the synchronization tool, so that the routes shown by the team CLI
+//! always match the ones that are actually created.
+//!
+//! Mailgun limits the size of the actions of each route, so lists with many members are split into
+//! multiple routes matching the same address, each with its own priority.
+
+/// Maximum size (in bytes) of the actions of a single Mailgun route.
+pub const ACTIONS_SIZE_LIMIT_BYTES: usize = 4000;
+
+/// A single Mailgun route forwarding a mailing list address to (a subset of) its members.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    /// The regex matching the recipient address of the route.
+    pub pattern: String,
+    pub priority: i32,
+    pub members: Vec<String>,
+}
+
+/// Convert a list address into the regex matching it in a Mailgun route.
+pub fn mangle_address(address: &str) -> Result<String, Error> {
+    // Escape dots since they have a special meaning in Python regexes
+    let mangled = address.replace('.', "\\.");
+
+    // Inject (?:\+.+)? before the '@' in the address to support '+' aliases like
+    // infra+botname@rust-lang.org
+    match mangled.find('@') {
+        Some(at_pos) => {
+            let (user, domain) = mangled.split_at(at_pos);
+            Ok(format!("^{}(?:\\+.+)?{}$", user, domain))
+        }
+        None => Err(Error::MissingAt(address.to_string())),
+    }
+}
+
+/// The action forwarding emails to a member of the list.
+pub fn route_action(member: &str) -> String {
+    format!("forward(\"{}\")", member)
+}
+
+/// Split the members of a list into as many routes as needed to respect the size limit of the
+/// actions of each route.
+pub fn partition(address: &str, members: &[String]) -> Result<Vec<Route>, Error> {
+    let base = Route {
+        pattern: mangle_address(address)?,
+        priority: 0,
+        members: Vec::new(),
+    };
+
+    let mut routes = Vec::new();
+    let mut current = base.clone();
+    let mut current_actions_len = 0;
+    for member in members {
+        let action_len = route_action(member).len();
+        if current_actions_len + action_len > ACTIONS_SIZE_LIMIT_BYTES {
+            let priority = current.priority + 1;
+            routes.push(current);
+            current = Route {
+                priority,
+                ..base.clone()
+            };
+            current_actions_len = 0;
+        }
+        current_actions_len += action_len;
+        current.members.push(member.clone());
+    }
+    routes.push(current);
+
+    Ok(routes)
+}
+
+#[derive(Debug)]
+pub enum Error {
+    MissingAt(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::MissingAt(address) => {
+                write!(f, "the address `{}` doesn't have any '@'", address)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partition() -> Result<(), Error> {
+        let members = (0..1000)
+            .map(|i| format!("member-{}@example.com", i))
+            .collect::<Vec<_>>();
+
+        let routes = partition("list@rust-lang.org", &members)?;
+        assert!(routes.len() > 1);
+        for (priority, route) in routes.iter().enumerate() {
+            assert_eq!(route.pattern, r"^list(?:\+.+)?@rust-lang\.org$");
+            assert_eq!(route.priority, priority as i32);
+            let size: usize = route.members.iter().map(|m| route_action(m).len()).sum();
+            assert!(size <= ACTIONS_SIZE_LIMIT_BYTES);
+        }
+        let all = routes
+            .into_iter()
+            .flat_map(|r| r.members)
+            .collect::<Vec<_>>();
+        assert_eq!(all, members);
+
+        // Like sync-team, a list without members still gets a (memberless) route.
+        let routes = partition("list@rust-lang.org", &[])?;
+        assert_eq!(routes.len(), 1);
+        assert!(routes[0].members.is_empty());
+
+        Ok(())
+    }
+}
@@ -49,6 +49,12 @@ pub struct GitHubTeam {
     pub org: String,
     pub name: String,
     pub members: Vec<u64>,
+    /// The GitHub IDs of the members with the maintainer role in the team.
+    #[serde(default)]
+    pub maintainers: Vec<u64>,
+    /// The name of the GitHub team (in the same org) this team is nested under.
+    #[serde(default)]
+    pub parent: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -95,6 +101,13 @@ pub struct Repos {
     pub repos: IndexMap<String, Vec<Repo>>,
 }
 
+/// The teams with write access or higher to each repo, keyed by `org/name`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RepoOwners {
+    #[serde(flatten)]
+    pub repos: IndexMap<String, Vec<String>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct List {
     pub address: String,
@@ -109,6 +122,8 @@ pub struct Lists {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ZulipGroup {
     pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub description: Option<String>,
     pub members: Vec<ZulipGroupMember>,
 }
 
@@ -165,16 +180,32 @@ pub struct Repo {
     pub name: String,
     pub description: String,
     pub homepage: Option<String>,
+    #[serde(default)]
+    pub topics: Vec<String>,
     pub bots: Vec<Bot>,
     pub teams: Vec<RepoTeam>,
     pub members: Vec<RepoMember>,
     pub branch_protections: Vec<BranchProtection>,
+    #[serde(default)]
+    pub autolinks: Vec<Autolink>,
     pub archived: bool,
     // This attribute is not synced by sync-team.
     pub private: bool,
     // Is the GitHub "Auto-merge" option enabled?
     // https://docs.github.com/en/pull-requests/collaborating-with-pull-requests/incorporating-changes-from-a-pull-request/automatically-merging-a-pull-request
     pub auto_merge_enabled: bool,
+    // The merge methods allowed on pull requests. When unset, the setting on
+    // GitHub is left untouched.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub allow_squash_merge: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub allow_merge_commit: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub allow_rebase_merge: Option<bool>,
+    /// The name of the default branch. When unset, the setting on GitHub is
+    /// left untouched. The branch must already exist to be made the default.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub default_branch: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -188,6 +219,14 @@ pub enum Bot {
     Renovate,
 }
 
+/// A reference (like `RUST-123`) automatically linked to an external URL.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Autolink {
+    pub key_prefix: String,
+    /// The URL to link to, where `<num>` is replaced by the number of the reference.
+    pub url_template: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct RepoTeam {
     pub name: String,
@@ -207,6 +246,7 @@ pub enum RepoPermission {
     Admin,
     Maintain,
     Triage,
+    Read,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -229,11 +269,22 @@ pub enum MergeBot {
 pub struct BranchProtection {
     pub pattern: String,
     pub dismiss_stale_review: bool,
+    #[serde(default)]
+    pub requires_code_owner_reviews: bool,
     pub mode: BranchProtectionMode,
     pub allowed_merge_teams: Vec<String>,
+    #[serde(default)]
+    pub bypass_actors: Vec<BypassActor>,
     pub merge_bots: Vec<MergeBot>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BypassActor {
+    Team(String),
+    User(String),
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Person {
     pub name: String,
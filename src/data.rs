@@ -202,7 +202,7 @@ impl Data {
     }
 }
 
-fn load_file<T: DeserializeOwned>(path: &Path) -> Result<T, Error> {
+pub(crate) fn load_file<T: DeserializeOwned>(path: &Path) -> Result<T, Error> {
     let content = std::fs::read_to_string(path)
         .with_context(|| format!("failed to read {}", path.display()))?;
     let parsed =
@@ -1,13 +1,21 @@
 use anyhow::{bail, Error};
-use reqwest::blocking::{Client, ClientBuilder, RequestBuilder};
-use reqwest::header::{self, HeaderValue};
-use reqwest::Method;
+use log::warn;
+use reqwest::blocking::{Client, ClientBuilder, RequestBuilder, Response};
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use reqwest::{Method, StatusCode};
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 static API_BASE: &str = "https://api.github.com/";
+static API_BASE_VAR: &str = "GITHUB_API_BASE_URL";
 static TOKEN_VAR: &str = "GITHUB_TOKEN";
 
+/// How many times a request is retried after hitting a rate limit.
+const RATE_LIMIT_RETRIES: u32 = 3;
+/// The longest we're willing to wait for a rate limit to reset before giving up.
+const RATE_LIMIT_MAX_WAIT: Duration = Duration::from_secs(5 * 60);
+
 #[derive(serde::Deserialize)]
 pub(crate) struct User {
     pub(crate) id: u64,
@@ -25,6 +33,8 @@ struct GraphResult<T> {
 
 #[derive(serde::Deserialize)]
 struct GraphError {
+    #[serde(rename = "type")]
+    kind: Option<String>,
     message: String,
 }
 
@@ -35,6 +45,7 @@ struct GraphNodes<T> {
 
 pub(crate) struct GitHubApi {
     http: Client,
+    base_url: String,
     token: Option<String>,
 }
 
@@ -45,6 +56,7 @@ impl GitHubApi {
                 .user_agent(crate::USER_AGENT)
                 .build()
                 .unwrap(),
+            base_url: api_base_url(),
             token: std::env::var(TOKEN_VAR).ok(),
         }
     }
@@ -55,10 +67,10 @@ impl GitHubApi {
         method: Method,
         url: &str,
     ) -> Result<RequestBuilder, Error> {
-        let url = if url.starts_with("https://") {
+        let url = if url.starts_with("https://") || url.starts_with(&self.base_url) {
             Cow::Borrowed(url)
         } else {
-            Cow::Owned(format!("{}{}", API_BASE, url))
+            Cow::Owned(format!("{}{}", self.base_url, url))
         };
         if require_auth {
             self.require_auth()?;
@@ -74,6 +86,28 @@ impl GitHubApi {
         Ok(req)
     }
 
+    /// Send a REST request, retrying it if GitHub rate limits it.
+    fn rest(&self, require_auth: bool, method: Method, url: &str) -> Result<Response, Error> {
+        let mut attempt = 0;
+        loop {
+            let response = self.prepare(require_auth, method.clone(), url)?.send()?;
+            if !is_rate_limited(response.status(), response.headers()) {
+                return Ok(response.error_for_status()?);
+            }
+
+            let delay = rate_limit_delay(response.headers(), attempt);
+            if attempt >= RATE_LIMIT_RETRIES || delay > RATE_LIMIT_MAX_WAIT {
+                bail!("the GitHub rate limit was exceeded");
+            }
+            warn!(
+                "hit the GitHub rate limit, retrying in {} seconds",
+                delay.as_secs()
+            );
+            std::thread::sleep(delay);
+            attempt += 1;
+        }
+    }
+
     fn graphql<R, V>(&self, query: &str, variables: V) -> Result<R, Error>
     where
         R: serde::de::DeserializeOwned,
@@ -84,12 +118,47 @@ impl GitHubApi {
             query: &'a str,
             variables: V,
         }
-        let res: GraphResult<R> = self
-            .prepare(true, Method::POST, "graphql")?
-            .json(&Request { query, variables })
-            .send()?
-            .error_for_status()?
-            .json()?;
+        let mut attempt = 0;
+        let res: GraphResult<R> = loop {
+            let response = self
+                .prepare(true, Method::POST, "graphql")?
+                .json(&Request {
+                    query,
+                    variables: &variables,
+                })
+                .send()?;
+
+            // GitHub reports rate limits either with an HTTP error or with a
+            // `RATE_LIMITED` error in the GraphQL response.
+            let delay = rate_limit_delay(response.headers(), attempt);
+            let res = if is_rate_limited(response.status(), response.headers()) {
+                None
+            } else {
+                let res: GraphResult<R> = response.error_for_status()?.json()?;
+                if res
+                    .errors
+                    .iter()
+                    .any(|e| e.kind.as_deref() == Some("RATE_LIMITED"))
+                {
+                    None
+                } else {
+                    Some(res)
+                }
+            };
+
+            match res {
+                Some(res) => break res,
+                None if attempt < RATE_LIMIT_RETRIES && delay <= RATE_LIMIT_MAX_WAIT => {
+                    warn!(
+                        "hit the GitHub rate limit, retrying in {} seconds",
+                        delay.as_secs()
+                    );
+                    std::thread::sleep(delay);
+                    attempt += 1;
+                }
+                None => bail!("the GitHub rate limit was exceeded"),
+            }
+        };
         if let Some(error) = res.errors.first() {
             bail!("graphql error: {}", error.message);
         } else if let Some(data) = res.data {
@@ -108,13 +177,98 @@ impl GitHubApi {
 
     pub(crate) fn user(&self, login: &str) -> Result<User, Error> {
         Ok(self
-            .prepare(false, Method::GET, &format!("users/{}", login))?
-            .send()?
-            .error_for_status()?
+            .rest(false, Method::GET, &format!("users/{}", login))?
             .json()?)
     }
 
-    pub(crate) fn usernames(&self, ids: &[u64]) -> Result<HashMap<u64, String>, Error> {
+    /// The date of the latest public event of a user, in ISO 8601 format.
+    ///
+    /// GitHub only returns the events of the last 90 days, so `None` means
+    /// the user had no public activity in that period.
+    pub(crate) fn last_public_event(&self, login: &str) -> Result<Option<String>, Error> {
+        #[derive(serde::Deserialize)]
+        struct Event {
+            created_at: String,
+        }
+
+        let events: Vec<Event> = self
+            .rest(
+                true,
+                Method::GET,
+                &format!("users/{login}/events/public?per_page=1"),
+            )?
+            .json()?;
+        Ok(events.into_iter().next().map(|event| event.created_at))
+    }
+
+    /// The names of the check runs and commit statuses reported on the latest
+    /// commit of a branch.
+    ///
+    /// This needs at least two requests per branch, as the results are paginated.
+    pub(crate) fn ci_contexts(
+        &self,
+        org: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<HashSet<String>, Error> {
+        #[derive(serde::Deserialize)]
+        struct CheckRuns {
+            total_count: usize,
+            check_runs: Vec<CheckRun>,
+        }
+        #[derive(serde::Deserialize)]
+        struct CheckRun {
+            name: String,
+        }
+        #[derive(serde::Deserialize)]
+        struct CombinedStatus {
+            total_count: usize,
+            statuses: Vec<Status>,
+        }
+        #[derive(serde::Deserialize)]
+        struct Status {
+            context: String,
+        }
+
+        let commit = format!("repos/{org}/{repo}/commits/{branch}");
+        let mut contexts = HashSet::new();
+        let mut fetched = 0;
+        for page in 1.. {
+            let check_runs: CheckRuns = self
+                .rest(
+                    true,
+                    Method::GET,
+                    &format!("{commit}/check-runs?per_page=100&page={page}"),
+                )?
+                .json()?;
+            let count = check_runs.check_runs.len();
+            fetched += count;
+            contexts.extend(check_runs.check_runs.into_iter().map(|run| run.name));
+            if count == 0 || fetched >= check_runs.total_count {
+                break;
+            }
+        }
+        let mut fetched = 0;
+        for page in 1.. {
+            let status: CombinedStatus = self
+                .rest(
+                    true,
+                    Method::GET,
+                    &format!("{commit}/status?per_page=100&page={page}"),
+                )?
+                .json()?;
+            let count = status.statuses.len();
+            fetched += count;
+            contexts.extend(status.statuses.into_iter().map(|status| status.context));
+            if count == 0 || fetched >= status.total_count {
+                break;
+            }
+        }
+        Ok(contexts)
+    }
+
+    /// Query the logins of the users with the given ids (at most 100 at a time).
+    fn query_usernames(&self, ids: &[u64]) -> Result<Vec<(u64, String)>, Error> {
         #[derive(serde::Deserialize)]
         #[serde(rename_all = "camelCase")]
         struct Usernames {
@@ -136,57 +290,328 @@ impl GitHubApi {
             }
         ";
 
-        let cant_resolve = |e: &Error| e.to_string().contains("Could not resolve to a node");
+        let res: GraphNodes<Usernames> = self.graphql(
+            QUERY,
+            Params {
+                ids: ids.iter().map(|id| user_node_id(*id)).collect(),
+            },
+        )?;
+        Ok(res
+            .nodes
+            .into_iter()
+            .flatten()
+            .map(|node| (node.database_id, node.login))
+            .collect())
+    }
 
+    /// The logins of the users with the given ids. The ids not belonging to
+    /// any GitHub user (for example because the account was deleted) are
+    /// missing from the result.
+    pub(crate) fn usernames(&self, ids: &[u64]) -> Result<HashMap<u64, String>, Error> {
         let mut result = HashMap::new();
         for chunk in ids.chunks(100) {
-            let res: GraphNodes<Usernames> = match self.graphql(
-                QUERY,
-                Params {
-                    ids: chunk.iter().map(|id| user_node_id(*id)).collect(),
-                },
-            ) {
-                Ok(res) => res,
-                Err(e) => {
-                    if cant_resolve(&e) {
-                        // This error happens when a user is deleted. Provide
-                        // a more helpful error message to pinpoint it:
-                        for id in chunk {
-                            if let Err(inner_e) = self.graphql::<GraphNodes<Usernames>, Params>(
-                                QUERY,
-                                Params {
-                                    ids: vec![user_node_id(*id)],
-                                },
-                            ) {
-                                if cant_resolve(&inner_e) {
-                                    bail!(
-                                        "failed to resolve user id {}: {}\n\
-                                        Check if the user has possibly deleted their account.",
-                                        id,
-                                        e
-                                    );
-                                } else {
-                                    bail!(
-                                        "failed to check resolve error: {}\n\
-                                        Original error: {}",
-                                        inner_e,
-                                        e
-                                    );
-                                }
-                            }
+            match self.query_usernames(chunk) {
+                Ok(res) => result.extend(res),
+                // A single unresolvable id fails the whole query, so find
+                // which ones are missing one by one.
+                Err(e) if cant_resolve(&e) => {
+                    for id in chunk {
+                        match self.query_usernames(&[*id]) {
+                            Ok(res) => result.extend(res),
+                            Err(e) if cant_resolve(&e) => {}
+                            Err(e) => return Err(e),
                         }
                     }
-                    return Err(e);
                 }
-            };
-            for node in res.nodes.into_iter().flatten() {
-                result.insert(node.database_id, node.login);
+                Err(e) => return Err(e),
             }
         }
         Ok(result)
     }
 }
 
+fn cant_resolve(e: &Error) -> bool {
+    e.to_string().contains("Could not resolve to a node")
+}
+
+/// Whether GitHub rejected the request because of a (primary or secondary) rate limit.
+fn is_rate_limited(status: StatusCode, headers: &HeaderMap) -> bool {
+    matches!(
+        status,
+        StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS
+    ) && (headers.contains_key(header::RETRY_AFTER)
+        || headers
+            .get("x-ratelimit-remaining")
+            .is_some_and(|remaining| remaining == "0"))
+}
+
+/// How long to wait before retrying a rate limited request, honoring the
+/// headers sent by GitHub and falling back to an exponential backoff.
+fn rate_limit_delay(headers: &HeaderMap, attempt: u32) -> Duration {
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|value: &HeaderValue| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok())
+    };
+    if let Some(seconds) = header(header::RETRY_AFTER.as_str()) {
+        return Duration::from_secs(seconds);
+    }
+    if header("x-ratelimit-remaining") == Some(0) {
+        if let Some(reset) = header("x-ratelimit-reset") {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            return Duration::from_secs(reset.saturating_sub(now) + 1);
+        }
+    }
+    Duration::from_secs(60 * 2u64.pow(attempt))
+}
+
+/// The base URL of the GitHub API, which can be overridden (for example to
+/// point to a mock server or to GitHub Enterprise) with `GITHUB_API_BASE_URL`.
+fn api_base_url() -> String {
+    match std::env::var(API_BASE_VAR) {
+        Ok(url) if url.ends_with('/') => url,
+        Ok(url) => format!("{url}/"),
+        Err(_) => API_BASE.to_string(),
+    }
+}
+
 fn user_node_id(id: u64) -> String {
     base64::encode(format!("04:User{id}"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_base_url_override() -> Result<(), Error> {
+        for base in &["http://127.0.0.1:1234/api", "http://127.0.0.1:1234/api/"] {
+            std::env::set_var(API_BASE_VAR, base);
+            let github = GitHubApi {
+                token: Some("token".into()),
+                ..GitHubApi::new()
+            };
+            std::env::remove_var(API_BASE_VAR);
+
+            let rest = github.prepare(false, Method::GET, "users/foo")?.build()?;
+            assert_eq!(rest.url().as_str(), "http://127.0.0.1:1234/api/users/foo");
+            let graphql = github.prepare(true, Method::POST, "graphql")?.build()?;
+            assert_eq!(graphql.url().as_str(), "http://127.0.0.1:1234/api/graphql");
+        }
+        Ok(())
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    #[test]
+    fn test_is_rate_limited() {
+        let retry_after = headers(&[("retry-after", "10")]);
+        let exhausted = headers(&[("x-ratelimit-remaining", "0")]);
+        let remaining = headers(&[("x-ratelimit-remaining", "10")]);
+
+        assert!(is_rate_limited(StatusCode::FORBIDDEN, &retry_after));
+        assert!(is_rate_limited(StatusCode::TOO_MANY_REQUESTS, &retry_after));
+        assert!(is_rate_limited(StatusCode::FORBIDDEN, &exhausted));
+        assert!(!is_rate_limited(StatusCode::FORBIDDEN, &remaining));
+        assert!(!is_rate_limited(StatusCode::FORBIDDEN, &HeaderMap::new()));
+        assert!(!is_rate_limited(StatusCode::OK, &exhausted));
+        assert!(!is_rate_limited(StatusCode::NOT_FOUND, &retry_after));
+    }
+
+    #[test]
+    fn test_rate_limit_delay() {
+        // `Retry-After` takes precedence over everything else.
+        let reset = (now() + 100).to_string();
+        let retry_after = headers(&[
+            ("retry-after", "10"),
+            ("x-ratelimit-remaining", "0"),
+            ("x-ratelimit-reset", &reset),
+        ]);
+        assert_eq!(rate_limit_delay(&retry_after, 2), Duration::from_secs(10));
+
+        // Otherwise, wait until the rate limit resets.
+        let exhausted = headers(&[
+            ("x-ratelimit-remaining", "0"),
+            ("x-ratelimit-reset", &reset),
+        ]);
+        let delay = rate_limit_delay(&exhausted, 0);
+        assert!(delay > Duration::from_secs(95) && delay <= Duration::from_secs(101));
+        let past = (now() - 100).to_string();
+        let reset_past = headers(&[("x-ratelimit-remaining", "0"), ("x-ratelimit-reset", &past)]);
+        assert_eq!(rate_limit_delay(&reset_past, 0), Duration::from_secs(1));
+
+        // The reset time is ignored if there are requests remaining.
+        let remaining = headers(&[
+            ("x-ratelimit-remaining", "1"),
+            ("x-ratelimit-reset", &reset),
+        ]);
+        assert_eq!(rate_limit_delay(&remaining, 0), Duration::from_secs(60));
+
+        // Without any header, fall back to an exponential backoff.
+        assert_eq!(
+            rate_limit_delay(&HeaderMap::new(), 0),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            rate_limit_delay(&HeaderMap::new(), 1),
+            Duration::from_secs(120)
+        );
+        assert_eq!(
+            rate_limit_delay(&HeaderMap::new(), 2),
+            Duration::from_secs(240)
+        );
+    }
+
+    /// Serve the given raw HTTP responses, one per connection, returning the
+    /// base URL of the server and the request lines it received.
+    fn mock_server(responses: Vec<String>) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        use std::io::{BufRead, BufReader, Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for response in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut content_length = 0;
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                requests.push(line.trim_end().to_string());
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    let header = header.trim_end().to_lowercase();
+                    if header.is_empty() {
+                        break;
+                    }
+                    if let Some(len) = header.strip_prefix("content-length:") {
+                        content_length = len.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                reader.get_mut().write_all(response.as_bytes()).unwrap();
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+    /// A raw HTTP response, telling the client to retry immediately if it's rate limited.
+    fn response(status: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {status}\r\nretry-after: 0\r\ncontent-type: application/json\r\n\
+             content-length: {}\r\nconnection: close\r\n\r\n{body}",
+            body.len()
+        )
+    }
+
+    fn rate_limited() -> String {
+        response("429 Too Many Requests", "")
+    }
+
+    fn ok(body: &str) -> String {
+        response("200 OK", body)
+    }
+
+    fn mock_api(url: String) -> GitHubApi {
+        GitHubApi {
+            base_url: url,
+            token: Some("token".into()),
+            ..GitHubApi::new()
+        }
+    }
+
+    #[test]
+    fn test_rest_retries_rate_limits() -> Result<(), Error> {
+        let (url, server) = mock_server(vec![
+            rate_limited(),
+            ok(r#"{"id": 1, "login": "foo", "name": null, "email": null}"#),
+        ]);
+        let user = mock_api(url).user("foo")?;
+        assert_eq!((user.id, user.login.as_str()), (1, "foo"));
+        assert_eq!(
+            server.join().unwrap(),
+            vec!["GET /users/foo HTTP/1.1", "GET /users/foo HTTP/1.1"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_graphql_retries_rate_limits() -> Result<(), Error> {
+        let (url, server) = mock_server(vec![
+            rate_limited(),
+            ok(r#"{"errors": [{"type": "RATE_LIMITED", "message": "rate limited"}]}"#),
+            ok(r#"{"data": {"nodes": [{"databaseId": 1, "login": "foo"}]}}"#),
+        ]);
+        let usernames = mock_api(url).usernames(&[1])?;
+        assert_eq!(usernames.get(&1).map(|s| s.as_str()), Some("foo"));
+        assert_eq!(server.join().unwrap(), vec!["POST /graphql HTTP/1.1"; 3]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_usernames_skips_missing_ids() -> Result<(), Error> {
+        let missing =
+            r#"{"errors": [{"type": "NOT_FOUND", "message": "Could not resolve to a node"}]}"#;
+        let (url, server) = mock_server(vec![
+            ok(missing),
+            ok(r#"{"data": {"nodes": [{"databaseId": 1, "login": "foo"}]}}"#),
+            ok(missing),
+        ]);
+        let usernames = mock_api(url).usernames(&[1, 2])?;
+        assert_eq!(usernames.len(), 1);
+        assert_eq!(usernames.get(&1).map(|s| s.as_str()), Some("foo"));
+        server.join().unwrap();
+        Ok(())
+    }
+
+    #[test]
+    fn test_ci_contexts_pagination() -> Result<(), Error> {
+        let (url, server) = mock_server(vec![
+            ok(r#"{"total_count": 2, "check_runs": [{"name": "a"}]}"#),
+            ok(r#"{"total_count": 2, "check_runs": [{"name": "b"}]}"#),
+            ok(r#"{"total_count": 1, "statuses": [{"context": "c"}]}"#),
+        ]);
+        let contexts = mock_api(url).ci_contexts("org", "repo", "main")?;
+        let mut contexts = contexts.into_iter().collect::<Vec<_>>();
+        contexts.sort();
+        assert_eq!(contexts, vec!["a", "b", "c"]);
+        let commit = "/repos/org/repo/commits/main";
+        assert_eq!(
+            server.join().unwrap(),
+            vec![
+                format!("GET {commit}/check-runs?per_page=100&page=1 HTTP/1.1"),
+                format!("GET {commit}/check-runs?per_page=100&page=2 HTTP/1.1"),
+                format!("GET {commit}/status?per_page=100&page=1 HTTP/1.1"),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_rate_limit_retries_exhausted() {
+        let (url, server) = mock_server(vec![rate_limited(); RATE_LIMIT_RETRIES as usize + 1]);
+        let err = mock_api(url).user("foo").err().unwrap();
+        assert_eq!(err.to_string(), "the GitHub rate limit was exceeded");
+        server.join().unwrap();
+    }
+}
@@ -4,21 +4,23 @@ mod data;
 #[macro_use]
 mod permissions;
 mod github;
+mod person_diff;
 mod schema;
 mod static_api;
 mod validate;
 mod zulip;
 
 const USER_AGENT: &str = "https://github.com/rust-lang/team (infra@rust-lang.org)";
+const EMAIL_ENCRYPTION_KEY_VAR: &str = "EMAIL_ENCRYPTION_KEY";
 
 use data::Data;
-use schema::{Email, Team, TeamKind};
+use schema::{Email, Person, Team, TeamKind};
 use zulip::ZulipApi;
 
-use crate::schema::RepoPermission;
-use anyhow::{bail, format_err, Error};
+use crate::schema::{Bot, RepoPermission, ZulipGroupMember};
+use anyhow::{bail, format_err, Context as _, Error};
 use log::{error, info, warn};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 use std::str::FromStr;
 use structopt::StructOpt;
@@ -42,6 +44,44 @@ impl FromStr for DumpIndividuaAccessGroupBy {
     }
 }
 
+enum OutputFormat {
+    Human,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(Self::Human),
+            "json" => Ok(Self::Json),
+            _ => Err(format!(
+                "Invalid output format {s}. Valid formats are 'human' or 'json'"
+            )),
+        }
+    }
+}
+
+enum TeamsFormat {
+    Human,
+    Dot,
+}
+
+impl FromStr for TeamsFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(Self::Human),
+            "dot" => Ok(Self::Dot),
+            _ => Err(format!(
+                "Invalid output format {s}. Valid formats are 'human' or 'dot'"
+            )),
+        }
+    }
+}
+
 #[derive(structopt::StructOpt)]
 #[structopt(name = "team", about = "manage the rust team members")]
 enum Cli {
@@ -49,22 +89,94 @@ enum Cli {
     Check {
         #[structopt(long = "strict", help = "fail if optional checks are not executed")]
         strict: bool,
+        #[structopt(
+            long = "strict-emails",
+            help = "require every member of any team to have an email address"
+        )]
+        strict_emails: bool,
         #[structopt(
             long = "skip",
             multiple = true,
             help = "skip one or more validation steps"
         )]
         skip: Vec<String>,
+        #[structopt(
+            long = "offline",
+            help = "skip the checks relying on network APIs without trying to use them"
+        )]
+        offline: bool,
+        #[structopt(
+            long = "only",
+            multiple = true,
+            conflicts_with = "skip",
+            help = "only run the given validation steps"
+        )]
+        only: Vec<String>,
+    },
+    #[structopt(
+        name = "check-person",
+        help = "check if the configuration of a single person is correct"
+    )]
+    CheckPerson { github_username: String },
+    #[structopt(
+        name = "check-file",
+        help = "check a single person or team file, without loading the rest of the repository"
+    )]
+    CheckFile { path: PathBuf },
+    #[structopt(
+        name = "list-checks",
+        help = "print the names of all the validation steps"
+    )]
+    ListChecks,
+    #[structopt(
+        name = "doctor",
+        help = "print which service credentials are available in the environment"
+    )]
+    Doctor {
+        #[structopt(long = "output", default_value = "human")]
+        output: OutputFormat,
     },
     #[structopt(
         name = "add-person",
         help = "add a new person from their GitHub profile"
     )]
-    AddPerson { github_name: String },
+    AddPerson {
+        github_name: String,
+        #[structopt(long = "team", help = "also add the person to the members of a team")]
+        team: Option<String>,
+    },
     #[structopt(name = "static-api", help = "generate the static API")]
-    StaticApi { dest: String },
-    #[structopt(name = "show-person", help = "print information about a person")]
-    ShowPerson { github_username: String },
+    StaticApi {
+        dest: String,
+        #[structopt(
+            long = "filter",
+            help = "only render the repos and teams whose name matches the glob"
+        )]
+        filter: Option<glob::Pattern>,
+        #[structopt(
+            long = "incremental",
+            help = "only rewrite the files that changed, and print their paths"
+        )]
+        incremental: bool,
+    },
+    #[structopt(
+        name = "show-person",
+        help = "print information about a person, looked up by GitHub username or email"
+    )]
+    ShowPerson {
+        github_username_or_email: String,
+        #[structopt(long = "json", help = "print the information as JSON")]
+        json: bool,
+    },
+    #[structopt(
+        name = "person-diff",
+        help = "print how the access of a person changed between two static API snapshots"
+    )]
+    PersonDiff {
+        github_username: String,
+        old: PathBuf,
+        new: PathBuf,
+    },
     #[structopt(name = "dump-teams", help = "Lists all teams")]
     DumpTeams {
         #[structopt(
@@ -84,16 +196,72 @@ enum Cli {
         include_project_groups: bool,
         #[structopt(long = "only-leads", help = "whether to list only leads of the team")]
         only_leads: bool,
+        #[structopt(
+            long = "format",
+            default_value = "human",
+            help = "the output format, either 'human' or 'dot' (a Graphviz graph of the subteams)"
+        )]
+        format: TeamsFormat,
     },
     #[structopt(name = "dump-team", help = "print the members of a team")]
-    DumpTeam { name: String },
+    DumpTeam {
+        name: String,
+        #[structopt(long = "format", default_value = "human")]
+        format: OutputFormat,
+    },
+    #[structopt(
+        name = "dump-empty-alumni",
+        help = "print the teams with an empty list of alumni"
+    )]
+    DumpEmptyAlumni,
+    #[structopt(name = "dump-alumni", help = "print the alumni of a team")]
+    DumpAlumni {
+        #[structopt(required_unless = "all")]
+        name: Option<String>,
+        #[structopt(
+            long = "all",
+            conflicts_with = "name",
+            help = "print the alumni of all the teams, grouped by team"
+        )]
+        all: bool,
+    },
     #[structopt(name = "dump-list", help = "print all the emails in a list")]
-    DumpList { name: String },
+    DumpList {
+        name: String,
+        #[structopt(
+            long = "expand",
+            help = "print the Mailgun routes the list is synchronized as"
+        )]
+        expand: bool,
+        #[structopt(
+            long = "stats",
+            conflicts_with = "expand",
+            help = "print the number of members and Mailgun routes of the list"
+        )]
+        stats: bool,
+    },
+    #[structopt(
+        name = "dump-zulip",
+        help = "print the Zulip user groups managed by the repository"
+    )]
+    DumpZulip,
     #[structopt(
         name = "dump-website",
         help = "dump website internationalization data as a .ftl file"
     )]
-    DumpWebsite,
+    DumpWebsite {
+        #[structopt(
+            long = "out",
+            help = "write the .ftl file to this path instead of printing it"
+        )]
+        out: Option<PathBuf>,
+        #[structopt(
+            long = "check",
+            requires = "out",
+            help = "check that the file passed to --out is up to date instead of writing it"
+        )]
+        check: bool,
+    },
     #[structopt(
         name = "dump-permission",
         help = "print all the people with a permission"
@@ -106,11 +274,77 @@ enum Cli {
     DumpIndividuaAccess {
         #[structopt(default_value = "repo", long)]
         group_by: DumpIndividuaAccessGroupBy,
+        #[structopt(long = "exclude-bots", help = "exclude the accounts of known bots")]
+        exclude_bots: bool,
+        #[structopt(
+            long = "include-teams",
+            help = "also include the access granted through teams"
+        )]
+        include_teams: bool,
+    },
+    #[structopt(
+        name = "dump-by-repo-permission",
+        help = "print everyone with at least the given permission level on any repo"
+    )]
+    DumpByRepoPermission {
+        level: RepoPermission,
+        #[structopt(long = "exclude-bots", help = "exclude the accounts of known bots")]
+        exclude_bots: bool,
+    },
+    #[structopt(
+        name = "dump-stale-people",
+        help = "print the active members without recent public GitHub activity"
+    )]
+    DumpStalePeople {
+        #[structopt(
+            long = "months",
+            default_value = "3",
+            help = "how many months without activity make someone stale"
+        )]
+        months: u64,
     },
     #[structopt(name = "encrypt-email", help = "encrypt an email address")]
-    EncryptEmail,
+    EncryptEmail(EmailEncryptionOpts),
     #[structopt(name = "decrypt-email", help = "decrypt an email address")]
-    DecryptEmail,
+    DecryptEmail(EmailEncryptionOpts),
+}
+
+#[derive(structopt::StructOpt)]
+struct EmailEncryptionOpts {
+    #[structopt(
+        long = "key-env",
+        help = "read the secret key from an environment variable instead of prompting for it"
+    )]
+    key_env: Option<String>,
+    #[structopt(
+        long = "stdin",
+        help = "read the address from the standard input instead of prompting for it"
+    )]
+    stdin: bool,
+}
+
+impl EmailEncryptionOpts {
+    fn address(&self, prompt: &str) -> Result<String, Error> {
+        if self.stdin {
+            let mut address = String::new();
+            std::io::stdin().read_line(&mut address)?;
+            Ok(address.trim().to_string())
+        } else {
+            Ok(dialoguer::Input::new()
+                .with_prompt(prompt)
+                .interact_text()?)
+        }
+    }
+
+    fn key(&self) -> Result<String, Error> {
+        match &self.key_env {
+            Some(var) => std::env::var(var)
+                .with_context(|| format!("failed to read the environment variable {var}")),
+            None => Ok(dialoguer::Password::new()
+                .with_prompt("Secret key")
+                .interact()?),
+        }
+    }
 }
 
 fn main() {
@@ -132,16 +366,95 @@ fn main() {
 
 fn run() -> Result<(), Error> {
     let cli = Cli::from_args();
+    // Checking a single file must not require the rest of the data to be valid.
+    if let Cli::CheckFile { path } = &cli {
+        return crate::validate::validate_file(path);
+    }
     let data = Data::load()?;
     match cli {
-        Cli::Check { strict, skip } => {
+        Cli::Check {
+            strict,
+            strict_emails,
+            offline,
+            skip,
+            only,
+        } => {
             crate::validate::validate(
                 &data,
                 strict,
+                strict_emails,
+                offline,
                 &skip.iter().map(|s| s.as_ref()).collect::<Vec<_>>(),
+                &only.iter().map(|s| s.as_ref()).collect::<Vec<_>>(),
             )?;
         }
-        Cli::AddPerson { ref github_name } => {
+        Cli::CheckPerson {
+            ref github_username,
+        } => {
+            crate::validate::validate_person(&data, github_username)?;
+        }
+        Cli::CheckFile { .. } => unreachable!("single files are checked before loading the data"),
+        Cli::ListChecks => {
+            for (api, names) in crate::validate::check_names() {
+                println!("{}:", api);
+                for name in names {
+                    println!("  {}", name);
+                }
+            }
+        }
+        Cli::Doctor { output } => {
+            // service -> (status, details)
+            let mut services = BTreeMap::new();
+            let missing = |err: Error| ("missing", Some(err.to_string()));
+            services.insert(
+                "github",
+                github::GitHubApi::new()
+                    .require_auth()
+                    .map_or_else(missing, |()| ("ok", None)),
+            );
+            services.insert(
+                "zulip",
+                ZulipApi::new()
+                    .require_auth()
+                    .map_or_else(missing, |()| ("ok", None)),
+            );
+            services.insert(
+                "email-encryption",
+                match std::env::var(EMAIL_ENCRYPTION_KEY_VAR) {
+                    Ok(key) => match check_email_encryption_key(&key) {
+                        Ok(()) => ("ok", None),
+                        Err(err) => ("invalid", Some(format!("{err:#}"))),
+                    },
+                    Err(_) => (
+                        "missing",
+                        Some(format!(
+                            "missing environment variable {EMAIL_ENCRYPTION_KEY_VAR}"
+                        )),
+                    ),
+                },
+            );
+            match output {
+                OutputFormat::Human => {
+                    for (service, (status, details)) in &services {
+                        match details {
+                            Some(details) => println!("{service}: {status} ({details})"),
+                            None => println!("{service}: {status}"),
+                        }
+                    }
+                }
+                OutputFormat::Json => {
+                    let statuses: BTreeMap<_, _> = services
+                        .iter()
+                        .map(|(service, (status, _))| (service, status))
+                        .collect();
+                    println!("{}", serde_json::to_string(&statuses)?);
+                }
+            }
+        }
+        Cli::AddPerson {
+            ref github_name,
+            ref team,
+        } => {
             #[derive(serde::Serialize)]
             #[serde(rename_all = "kebab-case")]
             struct PersonToAdd<'a> {
@@ -152,6 +465,24 @@ fn run() -> Result<(), Error> {
                 email: Option<&'a str>,
             }
 
+            let team_file = match team {
+                Some(team) => {
+                    if data.team(team).is_none() {
+                        bail!("unknown team: {}", team);
+                    }
+                    let team_file = PathBuf::from(format!("teams/{}.toml", team));
+                    if !team_file.is_file() {
+                        bail!(
+                            "the team {} is not defined in {}",
+                            team,
+                            team_file.display()
+                        );
+                    }
+                    Some(team_file)
+                }
+                None => None,
+            };
+
             let github = github::GitHubApi::new();
             let user = github.user(github_name)?;
             let github_name = user.login;
@@ -161,6 +492,16 @@ fn run() -> Result<(), Error> {
                 bail!("person already in the repo: {}", github_name);
             }
 
+            // Edit the team file before writing anything, to avoid leaving a
+            // half-done change behind if that fails.
+            let team_file = match team_file {
+                Some(team_file) => {
+                    let content = std::fs::read_to_string(&team_file)?;
+                    Some((team_file, add_team_member(&content, &github_name)?))
+                }
+                None => None,
+            };
+
             let file = format!("people/{}.toml", github_name);
             std::fs::write(
                 &file,
@@ -182,47 +523,57 @@ fn run() -> Result<(), Error> {
             )?;
 
             info!("written data to {}", file);
+
+            if let Some((team_file, content)) = team_file {
+                std::fs::write(&team_file, content)?;
+                info!(
+                    "added {} to the members in {}",
+                    github_name,
+                    team_file.display()
+                );
+            }
         }
-        Cli::StaticApi { ref dest } => {
+        Cli::StaticApi {
+            ref dest,
+            filter,
+            incremental,
+        } => {
             let dest = PathBuf::from(dest);
-            let generator = crate::static_api::Generator::new(&dest, &data)?;
-            generator.generate()?;
+            if incremental {
+                let generator = crate::static_api::Generator::new_incremental(&dest, &data)?
+                    .with_filter(filter);
+                for path in generator.generate_incremental()? {
+                    println!("{}", path.display());
+                }
+            } else {
+                let generator =
+                    crate::static_api::Generator::new(&dest, &data)?.with_filter(filter);
+                generator.generate()?;
+            }
         }
         Cli::ShowPerson {
-            ref github_username,
+            ref github_username_or_email,
+            json,
         } => {
-            let person = data
-                .person(github_username)
-                .ok_or_else(|| format_err!("unknown person"))?;
-
-            println!("-- {} --", person.name());
-            println!();
+            let person = find_person(&data, github_username_or_email)?;
 
-            println!("github: @{}", person.github());
-            if let Some(zulip_id) = person.zulip_id() {
+            let zulip_name = person.zulip_id().map(|zulip_id| {
                 let zulip = ZulipApi::new();
                 match zulip.require_auth() {
-                    Ok(()) => match zulip.get_user(zulip_id) {
-                        Ok(user) => println!("zulip: {} ({zulip_id})", user.name),
-                        Err(err) => {
-                            println!("zulip_id: {zulip_id}  # Failed to look up Zulip name: {err}")
-                        }
-                    },
-                    Err(err) => {
-                        // We have no authentication credentials, so don't even attempt the network access.
-                        println!("zulip_id: {zulip_id}  # Skipped name lookup: {err}");
-                    }
+                    Ok(()) => zulip
+                        .get_user(zulip_id)
+                        .map(|user| user.name)
+                        .map_err(|err| format!("Failed to look up Zulip name: {err}")),
+                    // We have no authentication credentials, so don't even attempt the network access.
+                    Err(err) => Err(format!("Skipped name lookup: {err}")),
                 }
-            }
-            if let Email::Present(email) = person.email() {
-                println!("email: {}", email);
-            }
-            println!();
+            });
 
             let mut bors_permissions = person.permissions().bors().clone();
             let mut other_permissions = person.permissions().booleans().clone();
 
-            println!("teams:");
+            // Permissions are only granted by the teams the person is a member
+            // of, they are not inherited from the parent teams (`subteam-of`).
             let mut teams: Vec<_> = data
                 .teams()
                 .filter_map(|team| match team.contains_person(&data, person) {
@@ -232,24 +583,100 @@ fn run() -> Result<(), Error> {
                 })
                 .collect::<Result<_, _>>()?;
             teams.sort_by_key(|team| team.name());
+            for team in &teams {
+                bors_permissions.extend(team.permissions().bors().clone());
+                other_permissions.extend(team.permissions().booleans().clone());
+
+                if team.leads().contains(person.github()) {
+                    bors_permissions.extend(team.leads_permissions().bors().clone());
+                    other_permissions.extend(team.leads_permissions().booleans().clone());
+                }
+            }
+
+            let mut bors_permissions: Vec<_> = bors_permissions.into_iter().collect();
+            bors_permissions.sort_by_key(|(repo, _)| repo.clone());
+            let mut other_permissions: Vec<_> = other_permissions
+                .into_iter()
+                .filter_map(|(key, value)| if value { Some(key) } else { None })
+                .collect();
+            other_permissions.sort();
+
+            if json {
+                #[derive(serde::Serialize)]
+                struct PersonJson<'a> {
+                    name: &'a str,
+                    github: &'a str,
+                    github_id: u64,
+                    zulip_id: Option<u64>,
+                    // Only present when the name could be looked up on Zulip.
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    zulip_name: Option<&'a str>,
+                    has_email: bool,
+                    teams: Vec<&'a str>,
+                    bors_permissions: BTreeMap<&'a str, BorsPermissionsJson>,
+                    other_permissions: &'a [String],
+                }
+                #[derive(serde::Serialize)]
+                struct BorsPermissionsJson {
+                    review: bool,
+                    #[serde(rename = "try")]
+                    try_: bool,
+                }
+
+                let person_json = PersonJson {
+                    name: person.name(),
+                    github: person.github(),
+                    github_id: person.github_id(),
+                    zulip_id: person.zulip_id(),
+                    zulip_name: zulip_name
+                        .as_ref()
+                        .and_then(|name| name.as_ref().ok())
+                        .map(|name| name.as_str()),
+                    has_email: matches!(person.email(), Email::Present(_)),
+                    teams: teams.iter().map(|team| team.name()).collect(),
+                    bors_permissions: bors_permissions
+                        .iter()
+                        .map(|(repo, perms)| {
+                            (
+                                repo.as_str(),
+                                BorsPermissionsJson {
+                                    review: perms.review(),
+                                    try_: perms.try_(),
+                                },
+                            )
+                        })
+                        .collect(),
+                    other_permissions: &other_permissions,
+                };
+                println!("{}", serde_json::to_string_pretty(&person_json)?);
+                return Ok(());
+            }
+
+            println!("-- {} --", person.name());
+            println!();
+
+            println!("github: @{}", person.github());
+            if let (Some(zulip_id), Some(zulip_name)) = (person.zulip_id(), &zulip_name) {
+                match zulip_name {
+                    Ok(name) => println!("zulip: {name} ({zulip_id})"),
+                    Err(err) => println!("zulip_id: {zulip_id}  # {err}"),
+                }
+            }
+            if let Email::Present(email) = person.email() {
+                println!("email: {}", email);
+            }
+            println!();
+
+            println!("teams:");
             if teams.is_empty() {
                 println!("  (none)");
             } else {
-                for team in teams {
+                for team in &teams {
                     println!("  - {}", team.name());
-                    bors_permissions.extend(team.permissions().bors().clone());
-                    other_permissions.extend(team.permissions().booleans().clone());
-
-                    if team.leads().contains(person.github()) {
-                        bors_permissions.extend(team.leads_permissions().bors().clone());
-                        other_permissions.extend(team.leads_permissions().booleans().clone());
-                    }
                 }
             }
             println!();
 
-            let mut bors_permissions: Vec<_> = bors_permissions.into_iter().collect();
-            bors_permissions.sort_by_key(|(repo, _)| repo.clone());
             println!("bors permissions:");
             if bors_permissions.is_empty() {
                 println!("  (none)");
@@ -266,11 +693,6 @@ fn run() -> Result<(), Error> {
             }
             println!();
 
-            let mut other_permissions: Vec<_> = other_permissions
-                .into_iter()
-                .filter_map(|(key, value)| if value { Some(key) } else { None })
-                .collect();
-            other_permissions.sort();
             println!("other permissions:");
             if other_permissions.is_empty() {
                 println!("  (none)");
@@ -280,26 +702,37 @@ fn run() -> Result<(), Error> {
                 }
             }
         }
+        Cli::PersonDiff {
+            ref github_username,
+            ref old,
+            ref new,
+        } => {
+            crate::person_diff::person_diff(github_username, old, new)?;
+        }
 
         Cli::DumpTeams {
             exclude_working_groups,
             exclude_subteams,
             include_project_groups,
             only_leads,
+            format,
         } => {
-            for team in data.teams() {
+            let teams = data.teams().filter(|team| {
                 let excluded_wg = exclude_working_groups && team.kind() == TeamKind::WorkingGroup;
                 let excluded_project_group =
                     !include_project_groups && team.kind() == TeamKind::ProjectGroup;
                 let excluded_sub_teams = exclude_subteams && team.subteam_of().is_some();
                 let excluded_marker_team = team.kind() == TeamKind::MarkerTeam;
-                if excluded_wg
+                !(excluded_wg
                     || excluded_project_group
                     || excluded_sub_teams
-                    || excluded_marker_team
-                {
-                    continue;
-                }
+                    || excluded_marker_team)
+            });
+            if let TeamsFormat::Dot = format {
+                print!("{}", dump_teams_dot(teams.collect()));
+                return Ok(());
+            }
+            for team in teams {
                 println!("{} ({}):", team.name(), team.kind());
                 if let Some(parent) = team.subteam_of() {
                     println!("  parent team: {}", parent);
@@ -310,43 +743,117 @@ fn run() -> Result<(), Error> {
             }
         }
 
-        Cli::DumpTeam { ref name } => {
+        Cli::DumpTeam { ref name, format } => {
             let team = data.team(name).ok_or_else(|| format_err!("unknown team"))?;
-            dump_team_members(team, &data, false, 0)?;
+            match format {
+                OutputFormat::Human => dump_team_members(team, &data, false, 0)?,
+                OutputFormat::Json => dump_team_json(team, &data)?,
+            }
+        }
+        Cli::DumpEmptyAlumni => {
+            let mut teams: Vec<_> = data
+                .teams()
+                .filter(
+                    |team| matches!(&team.raw_people().alumni, Some(alumni) if alumni.is_empty()),
+                )
+                .collect();
+            teams.sort_by_key(|team| team.name());
+            for team in teams {
+                if team.requires_alumni_entry() {
+                    println!("{}", team.name());
+                } else {
+                    println!("{} (the `alumni` entry is optional)", team.name());
+                }
+            }
+        }
+        Cli::DumpAlumni { ref name, all } => {
+            if all {
+                let mut teams: Vec<_> = data
+                    .teams()
+                    .filter(|team| !team.explicit_alumni().is_empty())
+                    .collect();
+                teams.sort_by_key(|team| team.name());
+                for team in teams {
+                    println!("{}:", team.name());
+                    for github in sorted_alumni(team) {
+                        println!("  - {}", github);
+                    }
+                }
+            } else if let Some(name) = name {
+                let team = data.team(name).ok_or_else(|| format_err!("unknown team"))?;
+                for github in sorted_alumni(team) {
+                    println!("{}", github);
+                }
+            }
         }
-        Cli::DumpList { ref name } => {
+        Cli::DumpList {
+            ref name,
+            expand,
+            stats,
+        } => {
             let list = data
                 .list(name)?
                 .ok_or_else(|| format_err!("unknown list"))?;
             let mut emails = list.emails().iter().collect::<Vec<_>>();
             emails.sort();
-            for email in emails {
-                println!("{}", email);
+            if expand {
+                for route in list_routes(list.address(), &emails)? {
+                    println!("{} (priority {}):", route.pattern, route.priority);
+                    for member in &route.members {
+                        println!("  - {}", member);
+                    }
+                }
+            } else if stats {
+                let routes = list_routes(list.address(), &emails)?;
+                println!("members: {}", emails.len());
+                println!("routes: {}", routes.len());
+            } else {
+                for email in emails {
+                    println!("{}", email);
+                }
             }
         }
-        Cli::DumpWebsite => {
-            println!(
-                "# Autogenerated by `cargo run dump-website` in https://github.com/rust-lang/team"
-            );
-            let mut teams: Vec<_> = data.teams().collect();
-            teams.sort_by_key(|team| team.name());
-            let mut roles = BTreeMap::new();
-            for team in teams {
-                if let Some(website) = team.website_data() {
-                    let name = team.name();
-                    println!("governance-team-{}-name = {}", name, website.name());
-                    println!(
-                        "governance-team-{}-description = {}\n",
-                        name,
-                        website.description()
-                    );
-                }
-                for role in team.roles() {
-                    roles.insert(&role.id, &role.description);
+        Cli::DumpZulip => {
+            let mut groups = data.zulip_groups()?.into_values().collect::<Vec<_>>();
+            groups.sort_by(|a, b| a.name().cmp(b.name()));
+            println!("zulip groups:");
+            if groups.is_empty() {
+                println!("  (none)");
+            }
+            for group in groups {
+                let members = group.members();
+                let without_id = members
+                    .iter()
+                    .filter(|m| matches!(m, ZulipGroupMember::MemberWithoutId { .. }))
+                    .count();
+                print!("  - {}: {} members", group.name(), members.len());
+                if without_id > 0 {
+                    print!(" ({without_id} without a Zulip id)");
                 }
+                println!();
             }
-            for (role_id, description) in roles {
-                println!("governance-role-{role_id} = {description}");
+        }
+        Cli::DumpWebsite { out, check } => {
+            let ftl = dump_website(&data);
+            match out {
+                Some(path) if check => {
+                    let existing = std::fs::read_to_string(&path)
+                        .with_context(|| format!("failed to read {}", path.display()))?;
+                    if existing != ftl {
+                        bail!(
+                            "{} is out of date, run `cargo run dump-website --out {}` to update it",
+                            path.display(),
+                            path.display()
+                        );
+                    }
+                    info!("{} is up to date", path.display());
+                }
+                Some(path) => {
+                    std::fs::write(&path, ftl)
+                        .with_context(|| format!("failed to write {}", path.display()))?;
+                    info!("written data to {}", path.display());
+                }
+                None => print!("{}", ftl),
             }
         }
         Cli::DumpPermission { ref name } => {
@@ -362,61 +869,210 @@ fn run() -> Result<(), Error> {
                 println!("{}", github_username);
             }
         }
-        Cli::DumpIndividuaAccess { group_by } => {
-            // user -> (repo, access)
-            let mut users: HashMap<String, Vec<(String, RepoPermission)>> = HashMap::default();
+        Cli::DumpIndividuaAccess {
+            group_by,
+            exclude_bots,
+            include_teams,
+        } => {
+            let github_teams = if include_teams {
+                github_team_members(&data)?
+            } else {
+                HashMap::new()
+            };
+
+            // (user, repo) -> (permission, source)
+            let mut accesses: HashMap<(String, String), Vec<(RepoPermission, String)>> =
+                HashMap::new();
+            // repo -> (team, permission)
+            let mut repo_teams: HashMap<String, Vec<(String, RepoPermission)>> = HashMap::new();
             for repo in data.repos() {
                 let repo_name = format!("{}/{}", repo.org, repo.name);
                 for (user, access) in &repo.access.individuals {
-                    users
-                        .entry(user.clone())
+                    if exclude_bots && Bot::is_bot_username(user) {
+                        continue;
+                    }
+                    accesses
+                        .entry((user.clone(), repo_name.clone()))
                         .or_default()
-                        .push((repo_name.clone(), access.clone()));
+                        .push((access.clone(), "individual".to_string()));
+                }
+                if !include_teams {
+                    continue;
                 }
+                for (team, access) in &repo.access.teams {
+                    repo_teams
+                        .entry(repo_name.clone())
+                        .or_default()
+                        .push((team.clone(), access.clone()));
+                    let members = github_teams
+                        .get(&(repo.org.as_str(), team.as_str()))
+                        .map(|members| members.as_slice())
+                        .unwrap_or_default();
+                    for member in members {
+                        if exclude_bots && Bot::is_bot_username(member) {
+                            continue;
+                        }
+                        accesses
+                            .entry((member.to_string(), repo_name.clone()))
+                            .or_default()
+                            .push((access.clone(), format!("team {team}")));
+                    }
+                }
+            }
+
+            // user -> (repo, effective permission, sources)
+            let mut users: HashMap<String, Vec<(String, RepoPermission, Vec<String>)>> =
+                HashMap::default();
+            for ((user, repo), mut grants) in accesses {
+                grants.sort();
+                // The effective permission is the highest one granted to the user.
+                let permission = grants
+                    .iter()
+                    .map(|(permission, _)| permission.clone())
+                    .max()
+                    .unwrap();
+                let sources = grants.into_iter().map(|(_, source)| source).collect();
+                users
+                    .entry(user)
+                    .or_default()
+                    .push((repo, permission, sources));
             }
-            let output: HashMap<String, Vec<(String, RepoPermission)>> = match group_by {
+            let output: HashMap<String, Vec<(String, RepoPermission, Vec<String>)>> = match group_by
+            {
                 DumpIndividuaAccessGroupBy::Person => users,
                 DumpIndividuaAccessGroupBy::Repo => {
-                    let mut repos: HashMap<String, Vec<(String, RepoPermission)>> = HashMap::new();
+                    let mut repos: HashMap<String, Vec<(String, RepoPermission, Vec<String>)>> =
+                        HashMap::new();
                     for (user, accesses) in users {
-                        for (repo, access) in accesses {
-                            repos.entry(repo).or_default().push((user.clone(), access));
+                        for (repo, permission, sources) in accesses {
+                            repos.entry(repo).or_default().push((
+                                user.clone(),
+                                permission,
+                                sources,
+                            ));
                         }
                     }
+                    for repo in repo_teams.keys() {
+                        repos.entry(repo.clone()).or_default();
+                    }
                     repos
                 }
             };
             let mut output = output.into_iter().collect::<Vec<_>>();
             output.sort_unstable_by_key(|(key, _)| key.clone());
             for (_, values) in output.iter_mut() {
-                values.sort_unstable_by_key(|(name, _)| name.clone());
+                values.sort_unstable_by_key(|(name, _, _)| name.clone());
             }
             for (key, values) in output {
                 println!("{key}");
-                for (name, permission) in values {
-                    println!("\t {name}: {permission:?}");
+                if let (DumpIndividuaAccessGroupBy::Repo, Some(teams)) =
+                    (&group_by, repo_teams.get_mut(&key))
+                {
+                    teams.sort();
+                    for (team, permission) in teams.iter() {
+                        println!("\t team {team}: {permission:?}");
+                    }
+                }
+                for (name, permission, sources) in values {
+                    if include_teams {
+                        println!("\t {name}: {permission:?} ({})", sources.join(", "));
+                    } else {
+                        println!("\t {name}: {permission:?}");
+                    }
                 }
             }
         }
-        Cli::EncryptEmail => {
-            let plain: String = dialoguer::Input::new()
-                .with_prompt("Plaintext address")
-                .interact_text()?;
-            let key = dialoguer::Password::new()
-                .with_prompt("Secret key")
-                .interact()?;
+        Cli::DumpByRepoPermission {
+            ref level,
+            exclude_bots,
+        } => {
+            let github_teams = github_team_members(&data)?;
+
+            // person -> (repo, permission, source)
+            let mut people: BTreeMap<&str, Vec<(String, &RepoPermission, String)>> =
+                BTreeMap::new();
+            for repo in data.repos() {
+                let repo_name = format!("{}/{}", repo.org, repo.name);
+                for (team, permission) in &repo.access.teams {
+                    if permission < level {
+                        continue;
+                    }
+                    let members = github_teams
+                        .get(&(repo.org.as_str(), team.as_str()))
+                        .map(|members| members.as_slice())
+                        .unwrap_or_default();
+                    for member in members {
+                        if exclude_bots && Bot::is_bot_username(member) {
+                            continue;
+                        }
+                        people.entry(member).or_default().push((
+                            repo_name.clone(),
+                            permission,
+                            format!("team {team}"),
+                        ));
+                    }
+                }
+                for (user, permission) in &repo.access.individuals {
+                    if permission < level || (exclude_bots && Bot::is_bot_username(user)) {
+                        continue;
+                    }
+                    people.entry(user).or_default().push((
+                        repo_name.clone(),
+                        permission,
+                        "individual".to_string(),
+                    ));
+                }
+            }
+            for (person, mut accesses) in people {
+                accesses.sort();
+                println!("{person}");
+                for (repo, permission, source) in accesses {
+                    println!("\t {repo}: {permission:?} ({source})");
+                }
+            }
+        }
+        Cli::DumpStalePeople { months } => {
+            let github = github::GitHubApi::new();
+            if let Err(err) = github.require_auth() {
+                warn!("couldn't look up the GitHub activity of people: {err}");
+                return Ok(());
+            }
+            let today = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_secs()
+                / 86400;
+            let cutoff = today as i64 - months as i64 * 30;
+
+            let mut active: Vec<_> = data.active_members()?.into_iter().collect();
+            active.sort_unstable();
+            // (last activity, person), people without any activity go first
+            let mut stale = Vec::new();
+            for person in active {
+                let last_event = github.last_public_event(person)?;
+                let last_day = last_event.as_deref().map(days_since_epoch).transpose()?;
+                if last_day.is_none_or(|day| day < cutoff) {
+                    stale.push((last_event, person));
+                }
+            }
+            stale.sort();
+            for (last_event, person) in stale {
+                match last_event {
+                    Some(date) => println!("{person}: last public activity on {}", &date[..10]),
+                    None => println!("{person}: no public activity in the last 90 days"),
+                }
+            }
+        }
+        Cli::EncryptEmail(opts) => {
+            let plain = opts.address("Plaintext address")?;
+            let key = opts.key()?;
             println!(
                 "{}",
                 rust_team_data::email_encryption::encrypt(&key, &plain)?
             );
         }
-        Cli::DecryptEmail => {
-            let encrypted: String = dialoguer::Input::new()
-                .with_prompt("Encrypted address")
-                .interact_text()?;
-            let key = dialoguer::Password::new()
-                .with_prompt("Secret key")
-                .interact()?;
+        Cli::DecryptEmail(opts) => {
+            let encrypted = opts.address("Encrypted address")?;
+            let key = opts.key()?;
             println!(
                 "{}",
                 rust_team_data::email_encryption::try_decrypt(&key, &encrypted)?
@@ -427,6 +1083,301 @@ fn run() -> Result<(), Error> {
     Ok(())
 }
 
+/// The number of days between the Unix epoch and an ISO 8601 date.
+fn days_since_epoch(date: &str) -> Result<i64, Error> {
+    let parse = |range: std::ops::Range<usize>| -> Result<i64, Error> {
+        date.get(range)
+            .and_then(|part| part.parse().ok())
+            .ok_or_else(|| format_err!("invalid date: {date}"))
+    };
+    let (year, month, day) = (parse(0..4)?, parse(5..7)?, parse(8..10)?);
+    // https://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    Ok(era * 146097 + day_of_era - 719468)
+}
+
+/// Ensure the email encryption key can encrypt and decrypt an address.
+fn check_email_encryption_key(key: &str) -> Result<(), Error> {
+    const TEST_ADDRESS: &str = "doctor@rust-lang.invalid";
+    let encrypted = rust_team_data::email_encryption::encrypt(key, TEST_ADDRESS)
+        .context("failed to encrypt a test address")?;
+    let decrypted = rust_team_data::email_encryption::try_decrypt(key, &encrypted)
+        .context("failed to decrypt a test address")?;
+    if decrypted != TEST_ADDRESS {
+        bail!("decrypting a test address returned a different address");
+    }
+    Ok(())
+}
+
+fn find_person<'a>(data: &'a Data, github_username_or_email: &str) -> Result<&'a Person, Error> {
+    if !github_username_or_email.contains('@') {
+        return data
+            .person(github_username_or_email)
+            .ok_or_else(|| format_err!("unknown person"));
+    }
+
+    let key = std::env::var(EMAIL_ENCRYPTION_KEY_VAR).ok();
+    if key.is_none() {
+        warn!(
+            "missing environment variable {EMAIL_ENCRYPTION_KEY_VAR}, \
+             encrypted addresses won't be matched"
+        );
+    }
+    for person in data.people() {
+        let Email::Present(email) = person.email() else {
+            continue;
+        };
+        let email = match &key {
+            Some(key) => rust_team_data::email_encryption::try_decrypt(key, email)
+                .with_context(|| format!("failed to decrypt the email of {}", person.github()))?,
+            None => email.to_string(),
+        };
+        if email.eq_ignore_ascii_case(github_username_or_email) {
+            return Ok(person);
+        }
+    }
+    bail!("no person has the email address {github_username_or_email}");
+}
+
+/// The Mailgun routes a list is synchronized as.
+fn list_routes(
+    address: &str,
+    emails: &[&String],
+) -> Result<Vec<rust_team_data::mailgun::Route>, Error> {
+    let key = std::env::var(EMAIL_ENCRYPTION_KEY_VAR).ok();
+    if key.is_none() {
+        warn!(
+            "missing environment variable {EMAIL_ENCRYPTION_KEY_VAR}, encrypted addresses \
+             won't be decrypted and the routes might differ from the synchronized ones"
+        );
+    }
+    let decrypt = |email: &str| match &key {
+        Some(key) => rust_team_data::email_encryption::try_decrypt(key, email),
+        None => Ok(email.to_string()),
+    };
+
+    let address = decrypt(address)?;
+    let emails = emails
+        .iter()
+        .map(|email| decrypt(email))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rust_team_data::mailgun::partition(&address, &emails)?)
+}
+
+fn dump_website(data: &Data) -> String {
+    let mut ftl = String::new();
+    ftl.push_str(
+        "# Autogenerated by `cargo run dump-website` in https://github.com/rust-lang/team\n",
+    );
+    let mut teams: Vec<_> = data.teams().collect();
+    teams.sort_by_key(|team| team.name());
+    let mut roles = BTreeMap::new();
+    for team in teams {
+        if let Some(website) = team.website_data() {
+            let name = team.name();
+            ftl.push_str(&format!(
+                "governance-team-{}-name = {}\n",
+                name,
+                website.name()
+            ));
+            ftl.push_str(&format!(
+                "governance-team-{}-description = {}\n\n",
+                name,
+                website.description()
+            ));
+        }
+        for role in team.roles() {
+            roles.insert(&role.id, &role.description);
+        }
+    }
+    for (role_id, description) in roles {
+        ftl.push_str(&format!("governance-role-{role_id} = {description}\n"));
+    }
+    ftl
+}
+
+/// Add a member to the `members` list of a team's TOML file, editing the
+/// text directly to preserve the formatting and comments of the file.
+fn add_team_member(team_toml: &str, github: &str) -> Result<String, Error> {
+    let mut lines: Vec<String> = team_toml.lines().map(|line| line.to_string()).collect();
+    let people = lines
+        .iter()
+        .position(|line| line.trim() == "[people]")
+        .ok_or_else(|| format_err!("the team file has no [people] section"))?;
+    let members = lines[people + 1..]
+        .iter()
+        .take_while(|line| !line.trim_start().starts_with('['))
+        .position(|line| line.trim_start().starts_with("members"))
+        .map(|pos| people + 1 + pos)
+        .ok_or_else(|| format_err!("the team file has no members list"))?;
+    let entry = format!("\"{github}\"");
+
+    // An inline list, like `members = ["foo", "bar"]`.
+    if let Some(end) = lines[members].rfind(']') {
+        let line = &lines[members];
+        let start = line
+            .find('[')
+            .ok_or_else(|| format_err!("the members of the team are not a list"))?;
+        let separator = if line[start + 1..end].trim().is_empty() {
+            ""
+        } else {
+            ", "
+        };
+        lines[members] = format!("{}{separator}{entry}{}", &line[..end], &line[end..]);
+    } else {
+        let end = lines[members + 1..]
+            .iter()
+            .position(|line| line.trim_start().starts_with(']'))
+            .map(|pos| members + 1 + pos)
+            .ok_or_else(|| format_err!("the members list of the team is not terminated"))?;
+        let entries: Vec<(usize, String)> = (members + 1..end)
+            .filter_map(|idx| {
+                let line = lines[idx].trim_start();
+                let name = line
+                    .strip_prefix('"')
+                    .or_else(|| line.split("github = \"").nth(1))?;
+                Some((idx, name.split('"').next()?.to_lowercase()))
+            })
+            .collect();
+        let indent = entries
+            .first()
+            .map(|(idx, _)| {
+                let line = &lines[*idx];
+                line[..line.len() - line.trim_start().len()].to_string()
+            })
+            .unwrap_or_else(|| "    ".to_string());
+        // Keep sorted lists sorted, and append to the other ones.
+        let sorted = entries.windows(2).all(|pair| pair[0].1 <= pair[1].1);
+        let position = if sorted {
+            entries
+                .iter()
+                .find(|(_, name)| *name > github.to_lowercase())
+                .map_or(end, |(idx, _)| *idx)
+        } else {
+            end
+        };
+        if position == end {
+            if let Some((last, _)) = entries.last() {
+                if !lines[*last].trim_end().ends_with(',') {
+                    lines[*last].push(',');
+                }
+            }
+        }
+        lines.insert(position, format!("{indent}{entry},"));
+    }
+
+    let mut result = lines.join("\n");
+    if team_toml.ends_with('\n') {
+        result.push('\n');
+    }
+    let team: Team = toml::from_str(&result)
+        .context("failed to add the member to the team file, please edit it manually")?;
+    if !team.explicit_members().iter().any(|m| m.github == github) {
+        bail!("failed to add the member to the team file, please edit it manually");
+    }
+    Ok(result)
+}
+
+/// Resolve the members of every GitHub team, keyed by organization and team name.
+fn github_team_members(data: &Data) -> Result<HashMap<(&str, &str), Vec<&str>>, Error> {
+    let mut github_teams: HashMap<(&str, &str), Vec<&str>> = HashMap::new();
+    for team in data.teams() {
+        for github_team in team.github_teams(data)? {
+            github_teams
+                .entry((github_team.org, github_team.name))
+                .or_default()
+                .extend(github_team.members.iter().map(|(name, _)| *name));
+        }
+    }
+    Ok(github_teams)
+}
+
+fn sorted_alumni(team: &Team) -> Vec<&str> {
+    let mut alumni: Vec<_> = team
+        .explicit_alumni()
+        .iter()
+        .map(|member| member.github.as_str())
+        .collect();
+    alumni.sort_by_key(|github| github.to_lowercase());
+    alumni
+}
+
+/// Render the subteam graph of the teams in the Graphviz format.
+fn dump_teams_dot(mut teams: Vec<&Team>) -> String {
+    teams.sort_by_key(|team| team.name());
+    let names: HashSet<_> = teams.iter().map(|team| team.name()).collect();
+
+    let mut dot = String::from("digraph teams {\n");
+    for team in &teams {
+        let style = match team.kind() {
+            TeamKind::Team => "shape=box",
+            TeamKind::WorkingGroup => "shape=ellipse",
+            TeamKind::ProjectGroup => "shape=ellipse, style=dashed",
+            TeamKind::MarkerTeam => "shape=box, style=dotted",
+        };
+        dot.push_str(&format!("    \"{}\" [{style}];\n", team.name()));
+    }
+    for team in &teams {
+        // Edges to excluded teams would add them back as unstyled nodes.
+        if let Some(parent) = team.subteam_of().filter(|parent| names.contains(parent)) {
+            dot.push_str(&format!("    \"{parent}\" -> \"{}\";\n", team.name()));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+fn dump_team_json(team: &Team, data: &Data) -> Result<(), Error> {
+    #[derive(serde::Serialize)]
+    struct TeamJson<'a> {
+        name: &'a str,
+        kind: String,
+        subteam_of: Option<&'a str>,
+        leads: Vec<&'a str>,
+        members: Vec<MemberJson<'a>>,
+    }
+    #[derive(serde::Serialize)]
+    struct MemberJson<'a> {
+        github: &'a str,
+        is_lead: bool,
+        roles: &'a [String],
+    }
+
+    let leads = team.leads();
+    let mut members = team.members(data)?.into_iter().collect::<Vec<_>>();
+    members.sort_unstable();
+    let mut sorted_leads = leads.iter().copied().collect::<Vec<_>>();
+    sorted_leads.sort_unstable();
+    let members = members
+        .into_iter()
+        .map(|member| MemberJson {
+            github: member,
+            is_lead: leads.contains(member),
+            roles: team
+                .explicit_members()
+                .iter()
+                .find(|explicit| explicit.github == member)
+                .map(|explicit| explicit.roles.as_slice())
+                .unwrap_or_default(),
+        })
+        .collect();
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&TeamJson {
+            name: team.name(),
+            kind: team.kind().to_string(),
+            subteam_of: team.subteam_of(),
+            leads: sorted_leads,
+            members,
+        })?
+    );
+    Ok(())
+}
+
 fn dump_team_members(
     team: &Team,
     data: &Data,
@@ -453,3 +1404,76 @@ fn dump_team_members(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn team(members: &str) -> String {
+        format!("name = \"foo\"\n\n[people]\nleads = []\n{members}\nalumni = []\n\n[permissions]\n")
+    }
+
+    #[test]
+    fn test_add_team_member_inline() -> Result<(), Error> {
+        assert_eq!(
+            add_team_member(&team("members = []"), "new")?,
+            team("members = [\"new\"]")
+        );
+        assert_eq!(
+            add_team_member(&team("members = [\"b\", \"a\"]"), "new")?,
+            team("members = [\"b\", \"a\", \"new\"]")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_team_member_multiline_sorted() -> Result<(), Error> {
+        assert_eq!(
+            add_team_member(&team("members = [\n  \"a\",\n  \"c\",\n]"), "B")?,
+            team("members = [\n  \"a\",\n  \"B\",\n  \"c\",\n]")
+        );
+        // The missing trailing comma of the last entry is added.
+        assert_eq!(
+            add_team_member(&team("members = [\n    \"a\",\n    \"b\"\n]"), "c")?,
+            team("members = [\n    \"a\",\n    \"b\",\n    \"c\",\n]")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_team_member_multiline_unsorted() -> Result<(), Error> {
+        assert_eq!(
+            add_team_member(&team("members = [\n    \"c\",\n    \"a\",\n]"), "b")?,
+            team("members = [\n    \"c\",\n    \"a\",\n    \"b\",\n]")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_team_member_multiline_tables() -> Result<(), Error> {
+        assert_eq!(
+            add_team_member(
+                &team("members = [\n\t{ github = \"a\", roles = [\"x\"] },\n\t\"c\",\n]"),
+                "b"
+            )?,
+            team("members = [\n\t{ github = \"a\", roles = [\"x\"] },\n\t\"b\",\n\t\"c\",\n]")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_team_member_multiline_empty() -> Result<(), Error> {
+        assert_eq!(
+            add_team_member(&team("members = [\n]"), "a")?,
+            team("members = [\n    \"a\",\n]")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_team_member_errors() {
+        assert!(add_team_member("name = \"foo\"\n", "a").is_err());
+        assert!(add_team_member("name = \"foo\"\n\n[people]\nleads = []\n", "a").is_err());
+        assert!(add_team_member(&team("members = [\n    \"a\","), "b").is_err());
+    }
+}
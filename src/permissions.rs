@@ -93,6 +93,13 @@ impl Permissions {
     pub(crate) fn validate(&self, what: String, config: &Config) -> Result<(), Error> {
         for boolean in self.booleans.keys() {
             if !config.permissions_bools().contains(boolean) {
+                if let Some(suggestion) = did_you_mean(boolean, config.permissions_bools()) {
+                    bail!(
+                        "unknown permission: {} (did you mean `{}`?)",
+                        boolean,
+                        suggestion
+                    );
+                }
                 bail!(
                     "unknown permission: {} (maybe add it to config.toml?)",
                     boolean
@@ -101,6 +108,13 @@ impl Permissions {
         }
         for (repo, perms) in self.bors.iter() {
             if !config.permissions_bors_repos().contains(repo) {
+                if let Some(suggestion) = did_you_mean(repo, config.permissions_bors_repos()) {
+                    bail!(
+                        "unknown bors repository: {} (did you mean `{}`?)",
+                        repo,
+                        suggestion
+                    );
+                }
                 bail!(
                     "unknown bors repository: {} (maybe add it to config.toml?)",
                     repo
@@ -119,6 +133,20 @@ impl Permissions {
     }
 }
 
+/// Find the closest known name to a misspelled one, if any is close enough.
+fn did_you_mean<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a String>,
+) -> Option<&'a str> {
+    let max_distance = std::cmp::max(1, name.len() / 3);
+    candidates
+        .into_iter()
+        .map(|candidate| (strsim::levenshtein(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by(|(d1, c1), (d2, c2)| d1.cmp(d2).then(c1.cmp(c2)))
+        .map(|(_, candidate)| candidate.as_str())
+}
+
 pub(crate) fn allowed_people<'a>(
     data: &'a Data,
     permission: &str,
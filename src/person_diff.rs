@@ -0,0 +1,127 @@
+use anyhow::{Context as _, Error};
+use rust_team_data::v1;
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+
+/// The teams, permissions and repository access of a person in a static API snapshot.
+struct PersonAccess {
+    teams: BTreeSet<String>,
+    permissions: BTreeSet<String>,
+    repos: BTreeSet<String>,
+}
+
+impl PersonAccess {
+    fn load(snapshot: &Path, github: &str) -> Result<Self, Error> {
+        let v1 = snapshot.join("v1");
+
+        let people: v1::People = load_json(&v1.join("people.json"))?;
+        let github_id = people.people.get(github).map(|person| person.github_id);
+
+        let all_teams: v1::Teams = load_json(&v1.join("teams.json"))?;
+        let teams: BTreeSet<_> = all_teams
+            .teams
+            .values()
+            .filter(|team| team.members.iter().any(|m| m.github == github))
+            .map(|team| team.name.clone())
+            .collect();
+        // The GitHub teams (as org and name) the person is a member of, which
+        // can be named differently than the teams they belong to.
+        let github_teams: HashSet<(&str, &str)> = all_teams
+            .teams
+            .values()
+            .filter_map(|team| team.github.as_ref())
+            .flat_map(|github| &github.teams)
+            .filter(|team| github_id.is_some_and(|id| team.members.contains(&id)))
+            .map(|team| (team.org.as_str(), team.name.as_str()))
+            .collect();
+
+        let mut permissions = BTreeSet::new();
+        let permissions_dir = v1.join("permissions");
+        for entry in std::fs::read_dir(&permissions_dir)
+            .with_context(|| format!("failed to read {}", permissions_dir.display()))?
+        {
+            let path = entry?.path();
+            let permission: v1::Permission = load_json(&path)?;
+            if permission.github_users.iter().any(|user| user == github) {
+                if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) {
+                    permissions.insert(name.to_string());
+                }
+            }
+        }
+
+        let mut repos = BTreeSet::new();
+        let all_repos: v1::Repos = load_json(&v1.join("repos.json"))?;
+        for repo in all_repos.repos.values().flatten() {
+            let full_name = format!("{}/{}", repo.org, repo.name);
+            for member in repo.members.iter().filter(|m| m.name == github) {
+                repos.insert(format!(
+                    "{full_name} ({})",
+                    permission_name(&member.permission)
+                ));
+            }
+            let repo_teams = repo
+                .teams
+                .iter()
+                .filter(|t| github_teams.contains(&(repo.org.as_str(), t.name.as_str())));
+            for team in repo_teams {
+                repos.insert(format!(
+                    "{full_name} ({} via {})",
+                    permission_name(&team.permission),
+                    team.name
+                ));
+            }
+        }
+
+        Ok(PersonAccess {
+            teams,
+            permissions,
+            repos,
+        })
+    }
+}
+
+fn permission_name(permission: &v1::RepoPermission) -> &'static str {
+    match permission {
+        v1::RepoPermission::Write => "write",
+        v1::RepoPermission::Admin => "admin",
+        v1::RepoPermission::Maintain => "maintain",
+        v1::RepoPermission::Triage => "triage",
+        v1::RepoPermission::Read => "read",
+    }
+}
+
+fn load_json<T: DeserializeOwned>(path: &Path) -> Result<T, Error> {
+    let content =
+        std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_slice(&content).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+fn print_changes(title: &str, old: &BTreeSet<String>, new: &BTreeSet<String>) {
+    println!("{title}:");
+    let mut changed = false;
+    for removed in old.difference(new) {
+        println!("  - {removed}");
+        changed = true;
+    }
+    for added in new.difference(old) {
+        println!("  + {added}");
+        changed = true;
+    }
+    if !changed {
+        println!("  (no changes)");
+    }
+}
+
+/// Print how the access of a person changed between two static API snapshots.
+pub(crate) fn person_diff(github: &str, old: &Path, new: &Path) -> Result<(), Error> {
+    let old = PersonAccess::load(old, github)?;
+    let new = PersonAccess::load(new, github)?;
+
+    print_changes("teams", &old.teams, &new.teams);
+    println!();
+    print_changes("permissions", &old.permissions, &new.permissions);
+    println!();
+    print_changes("repos", &old.repos, &new.repos);
+    Ok(())
+}
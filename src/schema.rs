@@ -12,6 +12,16 @@ pub(crate) struct Config {
     allowed_github_orgs: HashSet<String>,
     permissions_bors_repos: HashSet<String>,
     permissions_bools: HashSet<String>,
+    #[serde(default)]
+    placeholder_email_domains: HashSet<String>,
+    #[serde(default)]
+    require_sorted_members: bool,
+    #[serde(default)]
+    unsorted_members_teams: HashSet<String>,
+    #[serde(default)]
+    empty_github_teams: HashSet<String>,
+    #[serde(default)]
+    renovate_orgs: HashSet<String>,
 }
 
 impl Config {
@@ -30,6 +40,26 @@ impl Config {
     pub(crate) fn permissions_bools(&self) -> &HashSet<String> {
         &self.permissions_bools
     }
+
+    pub(crate) fn placeholder_email_domains(&self) -> &HashSet<String> {
+        &self.placeholder_email_domains
+    }
+
+    pub(crate) fn require_sorted_members(&self) -> bool {
+        self.require_sorted_members
+    }
+
+    pub(crate) fn unsorted_members_teams(&self) -> &HashSet<String> {
+        &self.unsorted_members_teams
+    }
+
+    pub(crate) fn empty_github_teams(&self) -> &HashSet<String> {
+        &self.empty_github_teams
+    }
+
+    pub(crate) fn renovate_orgs(&self) -> &HashSet<String> {
+        &self.renovate_orgs
+    }
 }
 
 // This is an enum to allow two kinds of values for the email field:
@@ -96,7 +126,7 @@ impl Person {
         }
     }
 
-    pub(crate) fn email(&self) -> Email {
+    pub(crate) fn email(&self) -> Email<'_> {
         match &self.email {
             EmailField::Disabled(false) => Email::Disabled,
             EmailField::Disabled(true) => Email::Missing,
@@ -126,9 +156,10 @@ impl Person {
     }
 }
 
-#[derive(serde_derive::Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(serde_derive::Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
 #[serde(rename_all = "kebab-case")]
 pub(crate) enum TeamKind {
+    #[default]
     Team,
     WorkingGroup,
     ProjectGroup,
@@ -150,12 +181,6 @@ impl std::fmt::Display for TeamKind {
     }
 }
 
-impl Default for TeamKind {
-    fn default() -> Self {
-        Self::Team
-    }
-}
-
 #[derive(serde_derive::Deserialize, Debug)]
 #[serde(deny_unknown_fields, rename_all = "kebab-case")]
 pub(crate) struct Team {
@@ -375,6 +400,7 @@ impl Team {
         for raw_group in zulip_groups {
             let mut group = ZulipGroup {
                 name: raw_group.name.clone(),
+                description: raw_group.description.clone(),
                 includes_team_members: raw_group.include_team_members,
                 members: Vec::new(),
             };
@@ -428,21 +454,24 @@ impl Team {
     pub(crate) fn github_teams<'a>(&'a self, data: &'a Data) -> Result<Vec<GitHubTeam<'a>>, Error> {
         let mut result = Vec::new();
         for github in &self.github {
-            let mut members = self
-                .members(data)?
-                .iter()
-                .filter_map(|name| data.person(name).map(|p| (p.github(), p.github_id())))
-                .collect::<Vec<_>>();
+            let mut names = self.members(data)?.into_iter().collect::<Vec<_>>();
             for team in &github.extra_teams {
-                members.extend(
+                names.extend(
                     data.team(team)
                         .ok_or_else(|| format_err!("missing team {}", team))?
-                        .members(data)?
-                        .iter()
-                        .filter_map(|name| data.person(name).map(|p| (p.github(), p.github_id()))),
+                        .members(data)?,
                 );
             }
+            let mut members = Vec::new();
+            let mut unknown_members = Vec::new();
+            for name in names {
+                match data.person(name) {
+                    Some(person) => members.push((person.github(), person.github_id())),
+                    None => unknown_members.push(name),
+                }
+            }
             members.sort_unstable();
+            unknown_members.sort_unstable();
             let name = github.team_name.as_deref().unwrap_or(&self.name);
 
             for org in &github.orgs {
@@ -450,6 +479,9 @@ impl Team {
                     org: org.as_str(),
                     name,
                     members: members.clone(),
+                    unknown_members: unknown_members.clone(),
+                    maintainers: &github.maintainers,
+                    parent: github.parent.as_deref(),
                 });
             }
         }
@@ -474,6 +506,42 @@ impl Team {
         &self.people.members
     }
 
+    /// Whether the team must contain an `alumni = […]` field (even if empty),
+    /// so that there is an obvious place to move contributors within the same
+    /// file when removing them from `members`.
+    pub(crate) fn requires_alumni_entry(&self) -> bool {
+        // Exhaustive destructuring to ensure this code is touched if a new
+        // "include" settings is introduced.
+        let TeamPeople {
+            leads: _,
+            members,
+            alumni: _,
+            included_teams,
+            include_team_leads,
+            include_wg_leads,
+            include_project_group_leads,
+            include_all_team_members,
+            include_all_alumni,
+        } = &self.people;
+
+        // Marker teams are exempt from this, as well as teams which comprise
+        // only members of other teams via `include-team-leads` or similar;
+        // they do not need `alumni = […]`. For these teams, the correct place
+        // to put alumni is in the same team they're being included from.
+        let exempt_team_kind = match self.kind {
+            TeamKind::MarkerTeam => true,
+            TeamKind::Team | TeamKind::WorkingGroup | TeamKind::ProjectGroup => false,
+        };
+        let exempt_composition = members.is_empty() // intentionally not self.members(data).is_empty()
+            && (*include_team_leads
+                || *include_wg_leads
+                || *include_project_group_leads
+                || *include_all_team_members
+                || *include_all_alumni
+                || !included_teams.is_empty());
+        !(exempt_team_kind || exempt_composition)
+    }
+
     pub(crate) fn explicit_alumni(&self) -> &[TeamMember] {
         self.people.alumni.as_ref().map_or(&[], Vec::as_slice)
     }
@@ -506,6 +574,12 @@ pub(crate) struct GitHubTeam<'a> {
     pub(crate) org: &'a str,
     pub(crate) name: &'a str,
     pub(crate) members: Vec<(&'a str, u64)>,
+    /// The names of the members who aren't people in the repo, left out of `members`.
+    pub(crate) unknown_members: Vec<&'a str>,
+    /// The GitHub usernames of the members with the maintainer role in the team.
+    pub(crate) maintainers: &'a [String],
+    /// The name of the GitHub team (in the same org) this team is nested under.
+    pub(crate) parent: Option<&'a str>,
 }
 
 impl std::cmp::PartialOrd for GitHubTeam<'_> {
@@ -574,6 +648,9 @@ struct GitHubData {
     orgs: Vec<String>,
     #[serde(default)]
     extra_teams: Vec<String>,
+    #[serde(default)]
+    maintainers: Vec<String>,
+    parent: Option<String>,
 }
 
 #[derive(serde_derive::Deserialize, Debug)]
@@ -632,7 +709,7 @@ impl WebsiteData {
         self.repo.as_deref()
     }
 
-    pub(crate) fn discord(&self) -> Option<DiscordInvite> {
+    pub(crate) fn discord(&self) -> Option<DiscordInvite<'_>> {
         if let (Some(url), Some(channel)) = (&self.discord_invite, &self.discord_name) {
             Some(DiscordInvite {
                 url: url.as_ref(),
@@ -679,6 +756,7 @@ pub(crate) struct TeamList {
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
 pub(crate) struct RawZulipGroup {
     pub(crate) name: String,
+    pub(crate) description: Option<String>,
     #[serde(default = "default_true")]
     pub(crate) include_team_members: bool,
     #[serde(default)]
@@ -710,6 +788,7 @@ impl List {
 #[derive(Debug)]
 pub(crate) struct ZulipGroup {
     name: String,
+    description: Option<String>,
     includes_team_members: bool,
     members: Vec<ZulipGroupMember>,
 }
@@ -719,6 +798,11 @@ impl ZulipGroup {
         &self.name
     }
 
+    /// A custom description of the group, if one was configured
+    pub(crate) fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
     /// Whether the group includes the members of the team its associated
     pub(crate) fn includes_team_members(&self) -> bool {
         self.includes_team_members
@@ -752,10 +836,22 @@ pub(crate) struct Repo {
     pub description: String,
     pub homepage: Option<String>,
     #[serde(default)]
+    pub topics: Vec<String>,
+    #[serde(default)]
     pub private_non_synced: Option<bool>,
+    #[serde(default)]
+    pub org_owners_admin_only: bool,
+    #[serde(default)]
+    pub allow_individual_access: bool,
+    pub allow_squash_merge: Option<bool>,
+    pub allow_merge_commit: Option<bool>,
+    pub allow_rebase_merge: Option<bool>,
+    pub default_branch: Option<String>,
     pub bots: Vec<Bot>,
     pub access: RepoAccess,
     #[serde(default)]
+    pub autolinks: Vec<Autolink>,
+    #[serde(default)]
     pub branch_protections: Vec<BranchProtection>,
 }
 
@@ -770,6 +866,41 @@ pub(crate) enum Bot {
     Renovate,
 }
 
+impl Bot {
+    pub(crate) const ALL: &'static [Bot] = &[
+        Bot::Bors,
+        Bot::Highfive,
+        Bot::Rustbot,
+        Bot::RustTimer,
+        Bot::Rfcbot,
+        Bot::Renovate,
+    ];
+
+    /// The name of the GitHub account the bot acts as.
+    pub(crate) fn github_username(&self) -> &'static str {
+        match self {
+            Bot::Bors => "bors",
+            Bot::Highfive => "rust-highfive",
+            Bot::Rustbot => "rustbot",
+            Bot::RustTimer => "rust-timer",
+            Bot::Rfcbot => "rfcbot",
+            Bot::Renovate => "renovate[bot]",
+        }
+    }
+
+    /// Whether the GitHub account belongs to one of the known bots.
+    pub(crate) fn is_bot_username(name: &str) -> bool {
+        Bot::ALL.iter().any(|bot| bot.github_username() == name)
+    }
+}
+
+#[derive(serde_derive::Deserialize, Debug)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct Autolink {
+    pub key_prefix: String,
+    pub url_template: String,
+}
+
 #[derive(serde_derive::Deserialize, Debug)]
 #[serde(deny_unknown_fields, rename_all = "kebab-case")]
 pub(crate) struct RepoAccess {
@@ -778,15 +909,34 @@ pub(crate) struct RepoAccess {
     pub individuals: HashMap<String, RepoPermission>,
 }
 
-#[derive(serde_derive::Deserialize, Debug, Clone)]
+// The variants are ordered from the lowest to the highest access level.
+#[derive(serde_derive::Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(deny_unknown_fields, rename_all = "kebab-case")]
 pub(crate) enum RepoPermission {
+    Read,
     Triage,
     Write,
     Maintain,
     Admin,
 }
 
+impl std::str::FromStr for RepoPermission {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(Self::Read),
+            "triage" => Ok(Self::Triage),
+            "write" => Ok(Self::Write),
+            "maintain" => Ok(Self::Maintain),
+            "admin" => Ok(Self::Admin),
+            _ => Err(format!(
+                "Invalid permission {s}. Valid permissions are 'read', 'triage', 'write', 'maintain' or 'admin'"
+            )),
+        }
+    }
+}
+
 #[derive(serde_derive::Deserialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub(crate) enum MergeBot {
@@ -802,11 +952,22 @@ pub(crate) struct BranchProtection {
     #[serde(default)]
     pub dismiss_stale_review: bool,
     #[serde(default)]
+    pub requires_code_owner_reviews: bool,
+    #[serde(default)]
     pub required_approvals: Option<u32>,
     #[serde(default = "default_true")]
     pub pr_required: bool,
     #[serde(default)]
     pub allowed_merge_teams: Vec<String>,
     #[serde(default)]
+    pub bypass_actors: Vec<BypassActor>,
+    #[serde(default)]
     pub merge_bots: Vec<MergeBot>,
 }
+
+#[derive(serde_derive::Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum BypassActor {
+    Team(String),
+    User(String),
+}
@@ -1,18 +1,25 @@
 use crate::data::Data;
 use crate::schema::{
-    Bot, Email, MergeBot, Permissions, RepoPermission, TeamKind, ZulipGroupMember,
+    Bot, BypassActor, Email, MergeBot, Permissions, RepoPermission, TeamKind, ZulipGroupMember,
 };
 use anyhow::{ensure, Context as _, Error};
+use glob::Pattern;
 use indexmap::IndexMap;
 use log::info;
 use rust_team_data::v1;
 use rust_team_data::v1::BranchProtectionMode;
-use std::collections::HashMap;
-use std::path::Path;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 pub(crate) struct Generator<'a> {
     dest: &'a Path,
     data: &'a Data,
+    filter: Option<Pattern>,
+    /// The paths (relative to `dest`) of the files generated so far.
+    generated: RefCell<HashSet<PathBuf>>,
+    /// The paths (relative to `dest`) of the files whose content changed.
+    changed: RefCell<Vec<PathBuf>>,
 }
 
 impl<'a> Generator<'a> {
@@ -20,9 +27,33 @@ impl<'a> Generator<'a> {
         if dest.is_dir() {
             std::fs::remove_dir_all(dest)?;
         }
+        Self::new_incremental(dest, data)
+    }
+
+    /// Create a generator that keeps the existing content of `dest`, only
+    /// rewriting the files whose content changed (see `generate_incremental`).
+    pub(crate) fn new_incremental(dest: &'a Path, data: &'a Data) -> Result<Generator<'a>, Error> {
         std::fs::create_dir_all(dest)?;
 
-        Ok(Generator { dest, data })
+        Ok(Generator {
+            dest,
+            data,
+            filter: None,
+            generated: RefCell::new(HashSet::new()),
+            changed: RefCell::new(Vec::new()),
+        })
+    }
+
+    /// Only render the repos and teams whose name matches the pattern.
+    pub(crate) fn with_filter(mut self, filter: Option<Pattern>) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    fn matches_filter(&self, name: &str) -> bool {
+        self.filter
+            .as_ref()
+            .is_none_or(|filter| filter.matches(name))
     }
 
     pub(crate) fn generate(&self) -> Result<(), Error> {
@@ -38,13 +69,48 @@ impl<'a> Generator<'a> {
         Ok(())
     }
 
+    /// Generate the static API, returning the paths (relative to `dest`) of
+    /// the files that were created, changed or removed.
+    ///
+    /// When a filter is set only part of the API is generated, so no files are
+    /// removed.
+    pub(crate) fn generate_incremental(&self) -> Result<Vec<PathBuf>, Error> {
+        self.generate()?;
+        if self.filter.is_none() {
+            self.remove_stale_files(self.dest)?;
+        }
+
+        let mut changed = self.changed.take();
+        changed.sort();
+        Ok(changed)
+    }
+
+    /// Remove the files in `dir` that weren't generated by this run.
+    fn remove_stale_files(&self, dir: &Path) -> Result<(), Error> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                self.remove_stale_files(&path)?;
+                continue;
+            }
+            let relative = path.strip_prefix(self.dest)?.to_path_buf();
+            if !self.generated.borrow().contains(&relative) {
+                info!("removing stale API object {}...", relative.display());
+                std::fs::remove_file(&path)?;
+                self.changed.borrow_mut().push(relative);
+            }
+        }
+        Ok(())
+    }
+
     fn generate_repos(&self) -> Result<(), Error> {
         let mut repos: IndexMap<String, Vec<v1::Repo>> = IndexMap::new();
         let repo_iter = self
             .data
             .repos()
             .map(|repo| (repo, false))
-            .chain(self.data.archived_repos().map(|repo| (repo, true)));
+            .chain(self.data.archived_repos().map(|repo| (repo, true)))
+            .filter(|(repo, _)| self.matches_filter(&repo.name));
 
         for (r, archived) in repo_iter {
             let branch_protections: Vec<_> = r
@@ -53,6 +119,7 @@ impl<'a> Generator<'a> {
                 .map(|b| v1::BranchProtection {
                     pattern: b.pattern.clone(),
                     dismiss_stale_review: b.dismiss_stale_review,
+                    requires_code_owner_reviews: b.requires_code_owner_reviews,
                     mode: if b.pr_required {
                         BranchProtectionMode::PrRequired {
                             ci_checks: b.ci_checks.clone(),
@@ -62,6 +129,14 @@ impl<'a> Generator<'a> {
                         BranchProtectionMode::PrNotRequired
                     },
                     allowed_merge_teams: b.allowed_merge_teams.clone(),
+                    bypass_actors: b
+                        .bypass_actors
+                        .iter()
+                        .map(|actor| match actor {
+                            BypassActor::Team(team) => v1::BypassActor::Team(team.clone()),
+                            BypassActor::User(user) => v1::BypassActor::User(user.clone()),
+                        })
+                        .collect(),
                     merge_bots: b
                         .merge_bots
                         .iter()
@@ -72,11 +147,12 @@ impl<'a> Generator<'a> {
                 })
                 .collect();
             let managed_by_bors = r.bots.contains(&Bot::Bors);
-            let repo = v1::Repo {
+            let mut repo = v1::Repo {
                 org: r.org.clone(),
                 name: r.name.clone(),
                 description: r.description.clone(),
                 homepage: r.homepage.clone(),
+                topics: r.topics.clone(),
                 private: r.private_non_synced.unwrap_or(false),
                 bots: r
                     .bots
@@ -100,6 +176,7 @@ impl<'a> Generator<'a> {
                             RepoPermission::Write => v1::RepoPermission::Write,
                             RepoPermission::Maintain => v1::RepoPermission::Maintain,
                             RepoPermission::Triage => v1::RepoPermission::Triage,
+                            RepoPermission::Read => v1::RepoPermission::Read,
                         };
                         v1::RepoTeam {
                             name: name.clone(),
@@ -117,6 +194,7 @@ impl<'a> Generator<'a> {
                             RepoPermission::Write => v1::RepoPermission::Write,
                             RepoPermission::Maintain => v1::RepoPermission::Maintain,
                             RepoPermission::Triage => v1::RepoPermission::Triage,
+                            RepoPermission::Read => v1::RepoPermission::Read,
                         };
                         v1::RepoMember {
                             name: name.clone(),
@@ -125,9 +203,24 @@ impl<'a> Generator<'a> {
                     })
                     .collect(),
                 branch_protections,
+                autolinks: r
+                    .autolinks
+                    .iter()
+                    .map(|autolink| v1::Autolink {
+                        key_prefix: autolink.key_prefix.clone(),
+                        url_template: autolink.url_template.clone(),
+                    })
+                    .collect(),
                 archived,
                 auto_merge_enabled: !managed_by_bors,
+                allow_squash_merge: r.allow_squash_merge,
+                allow_merge_commit: r.allow_merge_commit,
+                allow_rebase_merge: r.allow_rebase_merge,
+                default_branch: r.default_branch.clone(),
             };
+            // The access is stored in hash maps, sort it to keep the output stable.
+            repo.teams.sort_by(|a, b| a.name.cmp(&b.name));
+            repo.members.sort_by(|a, b| a.name.cmp(&b.name));
 
             self.add(&format!("v1/repos/{}.json", r.name), &repo)?;
             repos.entry(r.org.clone()).or_default().push(repo);
@@ -136,7 +229,28 @@ impl<'a> Generator<'a> {
             .values_mut()
             .for_each(|r| r.sort_by(|r1, r2| r1.name.cmp(&r2.name)));
 
+        // Precompute which teams own each repo, so that consumers don't have to.
+        let mut owners = IndexMap::new();
+        for repo in repos.values().flatten().filter(|repo| !repo.archived) {
+            let teams: Vec<_> = repo
+                .teams
+                .iter()
+                .filter(|team| {
+                    matches!(
+                        team.permission,
+                        v1::RepoPermission::Write
+                            | v1::RepoPermission::Maintain
+                            | v1::RepoPermission::Admin
+                    )
+                })
+                .map(|team| team.name.clone())
+                .collect();
+            owners.insert(format!("{}/{}", repo.org, repo.name), teams);
+        }
+        owners.sort_keys();
+
         self.add("v1/repos.json", &v1::Repos { repos })?;
+        self.add("v1/repo-owners.json", &v1::RepoOwners { repos: owners })?;
         Ok(())
     }
 
@@ -144,6 +258,9 @@ impl<'a> Generator<'a> {
         let mut teams = IndexMap::new();
 
         for team in self.data.teams() {
+            if !self.matches_filter(team.name()) {
+                continue;
+            }
             let mut website_roles = HashMap::new();
             for member in team.explicit_members().iter().cloned() {
                 website_roles.insert(member.github, member.roles);
@@ -188,7 +305,9 @@ impl<'a> Generator<'a> {
             let mut github_teams = team.github_teams(self.data)?;
             github_teams.sort();
 
-            let member_discord_ids = team.discord_ids(self.data)?;
+            // The members are stored in a hash set, sort them to keep the output stable.
+            let mut member_discord_ids = team.discord_ids(self.data)?;
+            member_discord_ids.sort_unstable();
 
             let team_data = v1::Team {
                 name: team.name().into(),
@@ -208,6 +327,13 @@ impl<'a> Generator<'a> {
                         .map(|team| v1::GitHubTeam {
                             org: team.org.to_string(),
                             name: team.name.to_string(),
+                            parent: team.parent.map(|parent| parent.to_string()),
+                            maintainers: team
+                                .members
+                                .iter()
+                                .filter(|(github, _)| team.maintainers.iter().any(|m| m == github))
+                                .map(|(_, id)| *id)
+                                .collect(),
                             members: team.members.into_iter().map(|(_, id)| id).collect(),
                         })
                         .collect::<Vec<_>>(),
@@ -289,6 +415,7 @@ impl<'a> Generator<'a> {
                 group.name().to_string(),
                 v1::ZulipGroup {
                     name: group.name().to_string(),
+                    description: group.description().map(String::from),
                     members: members
                         .into_iter()
                         .filter_map(|m| match m {
@@ -384,7 +511,11 @@ impl<'a> Generator<'a> {
     fn generate_zulip_map(&self) -> Result<(), Error> {
         let mut zulip_people = IndexMap::new();
 
-        for person in self.data.people() {
+        // Iterate in a stable order, so that the output doesn't change between
+        // runs if multiple people share the same Zulip ID.
+        let mut people = self.data.people().collect::<Vec<_>>();
+        people.sort_by_key(|person| person.github());
+        for person in people {
             if let Some(zulip_id) = person.zulip_id() {
                 zulip_people.insert(zulip_id, person.github_id());
             }
@@ -467,12 +598,18 @@ impl<'a> Generator<'a> {
 
     fn write(&self, path: &str, bytes: &[u8]) -> Result<(), Error> {
         let dest = self.dest.join(path);
+        self.generated.borrow_mut().insert(PathBuf::from(path));
+        // Leave unchanged files untouched when generating incrementally.
+        if std::fs::read(&dest).is_ok_and(|existing| existing == bytes) {
+            return Ok(());
+        }
         if let Some(parent) = dest.parent() {
             if !parent.exists() {
                 std::fs::create_dir_all(parent)?;
             }
         }
         std::fs::write(&dest, bytes)?;
+        self.changed.borrow_mut().push(PathBuf::from(path));
         Ok(())
     }
 }
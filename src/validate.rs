@@ -1,14 +1,16 @@
-use crate::data::Data;
+use crate::data::{load_file, Data};
 use crate::github::GitHubApi;
 use crate::schema::{
-    Bot, Email, MergeBot, Permissions, Team, TeamKind, TeamPeople, ZulipGroupMember,
+    Bot, BypassActor, Config, Email, MergeBot, Permissions, Person, RepoPermission, Team, TeamKind,
+    ZulipGroupMember,
 };
 use crate::zulip::ZulipApi;
 use anyhow::{bail, Error};
-use log::{error, warn};
+use log::{error, info, warn};
 use regex::Regex;
 use std::collections::hash_map::{Entry, HashMap};
 use std::collections::HashSet;
+use std::path::Path;
 
 macro_rules! checks {
     ($($f:ident,)*) => {
@@ -21,41 +23,107 @@ macro_rules! checks {
     }
 }
 
+/// Checks run before all the others, stopping the validation if they fail:
+/// resolving the members of teams with inclusion cycles would never terminate.
+#[allow(clippy::type_complexity)]
+static PRELIMINARY_CHECKS: &[Check<fn(&Data, &mut Vec<String>)>] =
+    checks![validate_member_inclusions,];
+
 #[allow(clippy::type_complexity)]
 static CHECKS: &[Check<fn(&Data, &mut Vec<String>)>] = checks![
     validate_name_prefixes,
     validate_subteam_of,
     validate_team_leads,
+    validate_included_leads,
     validate_team_members,
+    validate_alumni_team,
+    validate_inclusion_settings,
     validate_alumni,
+    validate_sorted_members,
     validate_archived_teams,
-    validate_inactive_members,
-    validate_list_email_addresses,
     validate_list_extra_people,
     validate_list_extra_teams,
     validate_list_addresses,
-    validate_people_addresses,
+    validate_unique_github_ids,
     validate_duplicate_permissions,
     validate_permissions,
     validate_rfcbot_labels,
     validate_rfcbot_exclude_members,
+    validate_rfcbot_voters,
     validate_team_names,
     validate_github_teams,
+    validate_github_team_members,
     validate_zulip_stream_name,
     validate_subteam_of_required,
-    validate_discord_team_members_have_discord_ids,
     validate_unique_zulip_groups,
     validate_zulip_group_ids,
     validate_zulip_group_extra_people,
     validate_repos,
+    validate_repo_bots,
+    validate_repo_description_length,
+    validate_repo_topics,
+    validate_repo_default_branch,
+    validate_repo_merge_methods,
+    validate_repo_autolinks,
     validate_branch_protections,
+    validate_archived_repo_branch_protections,
     validate_member_roles,
 ];
 
+/// Checks whose findings might be intentional: they are reported as warnings
+/// and never cause the validation to fail.
+#[allow(clippy::type_complexity)]
+static WARNING_CHECKS: &[Check<fn(&Data, &mut Vec<String>)>] = checks![
+    validate_repo_admins,
+    validate_duplicate_names,
+    validate_duplicate_ci_checks,
+    validate_archived_repos_config,
+    validate_repo_homepages,
+    validate_discord_roles_have_members,
+    validate_individual_access,
+    validate_marker_teams,
+];
+
+/// Checks enforcing policies that are only enabled with `--strict-emails`.
+#[allow(clippy::type_complexity)]
+static STRICT_EMAILS_CHECKS: &[Check<fn(&Data, &mut Vec<String>)>] =
+    checks![validate_team_email_addresses,];
+
+/// Checks auditing the data for stale entries, only reported as warnings with `--strict`.
+#[allow(clippy::type_complexity)]
+static STRICT_WARNING_CHECKS: &[Check<fn(&Data, &mut Vec<String>)>] =
+    checks![validate_unused_people, validate_unlisted_rust_lang_emails,];
+
+/// Checks reported as errors with `--strict`, and as warnings otherwise.
+#[allow(clippy::type_complexity)]
+static STRICT_ERROR_CHECKS: &[Check<fn(&Data, &mut Vec<String>)>] =
+    checks![validate_nonempty_github_teams,];
+
+/// Checks concerning people, which can be restricted to a single person (see
+/// `validate_person`).
+#[allow(clippy::type_complexity)]
+static PERSON_CHECKS: &[Check<fn(&Data, Option<&Person>, &mut Vec<String>)>] = checks![
+    validate_inactive_members,
+    validate_list_email_addresses,
+    validate_people_addresses,
+    validate_discord_team_members_have_discord_ids,
+];
+
+/// Checks concerning people whose findings are reported as warnings.
+#[allow(clippy::type_complexity)]
+static PERSON_WARNING_CHECKS: &[Check<fn(&Data, Option<&Person>, &mut Vec<String>)>] =
+    checks![validate_placeholder_emails,];
+
 #[allow(clippy::type_complexity)]
 static GITHUB_CHECKS: &[Check<fn(&Data, &GitHubApi, &mut Vec<String>)>] =
     checks![validate_github_usernames,];
 
+/// Checks relying on the GitHub API whose findings are reported as warnings.
+/// As they are expensive, they only run when explicitly named in `--only`.
+#[allow(clippy::type_complexity)]
+static GITHUB_WARNING_CHECKS: &[Check<fn(&Data, &GitHubApi, &mut Vec<String>)>] =
+    checks![validate_branch_protection_ci_contexts,];
+
 #[allow(clippy::type_complexity)]
 static ZULIP_CHECKS: &[Check<fn(&Data, &ZulipApi, &mut Vec<String>)>] =
     checks![validate_zulip_users,];
@@ -65,20 +133,186 @@ struct Check<F> {
     name: &'static str,
 }
 
-pub(crate) fn validate(data: &Data, strict: bool, skip: &[&str]) -> Result<(), Error> {
+/// The names of all the checks, grouped by the API they require (if any).
+pub(crate) fn check_names() -> Vec<(&'static str, Vec<&'static str>)> {
+    vec![
+        (
+            "no API",
+            PRELIMINARY_CHECKS
+                .iter()
+                .chain(CHECKS)
+                .chain(STRICT_EMAILS_CHECKS)
+                .chain(WARNING_CHECKS)
+                .chain(STRICT_WARNING_CHECKS)
+                .chain(STRICT_ERROR_CHECKS)
+                .map(|check| check.name)
+                .chain(
+                    PERSON_CHECKS
+                        .iter()
+                        .chain(PERSON_WARNING_CHECKS)
+                        .map(|check| check.name),
+                )
+                .collect(),
+        ),
+        (
+            "GitHub API",
+            GITHUB_CHECKS
+                .iter()
+                .chain(GITHUB_WARNING_CHECKS)
+                .map(|check| check.name)
+                .collect(),
+        ),
+        (
+            "Zulip API",
+            ZULIP_CHECKS.iter().map(|check| check.name).collect(),
+        ),
+    ]
+}
+
+/// Check a single person or team file, without loading the rest of the repository.
+///
+/// Only the checks not depending on other files are executed.
+pub(crate) fn validate_file(path: &Path) -> Result<(), Error> {
+    fn dir_name(dir: Option<&Path>) -> Option<&str> {
+        dir?.file_name()?.to_str()
+    }
+
+    let config: Config = load_file(Path::new("config.toml"))?;
+    let parent = path.parent();
+    let kind = (dir_name(parent), dir_name(parent.and_then(Path::parent)));
+
+    let mut errors = Vec::new();
+    match kind {
+        (Some("people"), _) => {
+            let person: Person = load_file(path)?;
+            let person_checks = vec![
+                person.validate(),
+                person
+                    .permissions()
+                    .validate(format!("user `{}`", person.github()), &config),
+            ];
+            wrapper(person_checks.into_iter(), &mut errors, |result, _| result);
+        }
+        (Some("teams"), _) | (Some("archive"), Some("teams")) => {
+            let team: Team = load_file(path)?;
+            let team_checks = vec![
+                team_name_prefixes(&team),
+                team_name_kebab_case(&team),
+                team.permissions()
+                    .validate(format!("team `{}`", team.name()), &config),
+                team.leads_permissions()
+                    .validate(format!("team `{}`", team.name()), &config),
+            ];
+            wrapper(team_checks.into_iter(), &mut errors, |result, _| result);
+        }
+        _ => bail!(
+            "{} is not a person or team file (in the `people`, `teams` or `teams/archive` directory)",
+            path.display()
+        ),
+    }
+
+    if !errors.is_empty() {
+        for err in &errors {
+            error!("validation error: {}", err);
+        }
+        bail!("{} validation errors found", errors.len());
+    }
+    Ok(())
+}
+
+pub(crate) fn validate(
+    data: &Data,
+    strict: bool,
+    strict_emails: bool,
+    offline: bool,
+    skip: &[&str],
+    only: &[&str],
+) -> Result<(), Error> {
+    let all_checks = check_names()
+        .into_iter()
+        .flat_map(|(_, names)| names)
+        .collect::<Vec<_>>();
+    for name in only {
+        if !all_checks.contains(name) {
+            bail!("unknown check: {}", name);
+        }
+    }
+    // Whether a check should run, logging the checks explicitly skipped.
+    let should_run = |name: &str| {
+        if skip.contains(&name) {
+            warn!("skipped check: {}", name);
+            return false;
+        }
+        only.is_empty() || only.contains(&name)
+    };
+
     let mut errors = Vec::new();
 
+    for check in PRELIMINARY_CHECKS {
+        if should_run(check.name) {
+            (check.f)(data, &mut errors);
+        }
+    }
+    if !errors.is_empty() {
+        for err in &errors {
+            error!("validation error: {}", err);
+        }
+        bail!("{} validation errors found", errors.len());
+    }
+
     for check in CHECKS {
-        if skip.contains(&check.name) {
-            warn!("skipped check: {}", check.name);
-            continue;
+        if should_run(check.name) {
+            (check.f)(data, &mut errors);
+        }
+    }
+    for check in PERSON_CHECKS {
+        if should_run(check.name) {
+            (check.f)(data, None, &mut errors);
         }
+    }
 
-        (check.f)(data, &mut errors);
+    for check in STRICT_EMAILS_CHECKS {
+        // Naming the check in `--only` enables it even without `--strict-emails`.
+        if (strict_emails || only.contains(&check.name)) && should_run(check.name) {
+            (check.f)(data, &mut errors);
+        }
     }
 
+    let mut warnings = Vec::new();
+    for check in WARNING_CHECKS {
+        if should_run(check.name) {
+            (check.f)(data, &mut warnings);
+        }
+    }
+    for check in PERSON_WARNING_CHECKS {
+        if should_run(check.name) {
+            (check.f)(data, None, &mut warnings);
+        }
+    }
+    for check in STRICT_WARNING_CHECKS {
+        // Naming the check in `--only` enables it even without `--strict`.
+        if (strict || only.contains(&check.name)) && should_run(check.name) {
+            (check.f)(data, &mut warnings);
+        }
+    }
+    for check in STRICT_ERROR_CHECKS {
+        if should_run(check.name) {
+            (check.f)(data, if strict { &mut errors } else { &mut warnings });
+        }
+    }
+
+    let github_checks_selected = GITHUB_CHECKS
+        .iter()
+        .any(|check| only.is_empty() || only.contains(&check.name))
+        || GITHUB_WARNING_CHECKS
+            .iter()
+            .any(|check| only.contains(&check.name));
     let github = GitHubApi::new();
-    if let Err(err) = github.require_auth() {
+    if offline {
+        info!("offline mode, skipping the checks relying on the GitHub API");
+    } else if !github_checks_selected {
+        // None of the checks relying on the GitHub API were requested.
+    } else if let Err(err) = github.require_auth() {
         if strict {
             return Err(err);
         } else {
@@ -87,30 +321,42 @@ pub(crate) fn validate(data: &Data, strict: bool, skip: &[&str]) -> Result<(), E
         }
     } else {
         for check in GITHUB_CHECKS {
-            if skip.contains(&check.name) {
-                warn!("skipped check: {}", check.name);
-                continue;
+            if should_run(check.name) {
+                (check.f)(data, &github, &mut errors);
+            }
+        }
+        for check in GITHUB_WARNING_CHECKS {
+            if only.contains(&check.name) && should_run(check.name) {
+                (check.f)(data, &github, &mut warnings);
             }
-
-            (check.f)(data, &github, &mut errors);
         }
     }
 
+    let zulip_checks_selected = ZULIP_CHECKS
+        .iter()
+        .any(|check| only.is_empty() || only.contains(&check.name));
     let zulip = ZulipApi::new();
-    if let Err(err) = zulip.require_auth() {
+    if offline {
+        info!("offline mode, skipping the checks relying on the Zulip API");
+    } else if !zulip_checks_selected {
+        // None of the checks relying on the Zulip API were requested.
+    } else if let Err(err) = zulip.require_auth() {
         warn!("couldn't perform checks relying on the Zulip API, some errors will not be detected");
         warn!("cause: {}", err);
     } else {
         for check in ZULIP_CHECKS {
-            if skip.contains(&check.name) {
-                warn!("skipped check: {}", check.name);
-                continue;
+            if should_run(check.name) {
+                (check.f)(data, &zulip, &mut errors);
             }
-
-            (check.f)(data, &zulip, &mut errors);
         }
     }
 
+    warnings.sort();
+    warnings.dedup();
+    for warning in &warnings {
+        warn!("validation warning: {}", warning);
+    }
+
     if !errors.is_empty() {
         errors.sort();
         errors.dedup_by(|a, b| a == b);
@@ -125,8 +371,92 @@ pub(crate) fn validate(data: &Data, strict: bool, skip: &[&str]) -> Result<(), E
     Ok(())
 }
 
+/// Validate a single person, only reporting the findings of the checks
+/// concerning people that mention them.
+pub(crate) fn validate_person(data: &Data, github_username: &str) -> Result<(), Error> {
+    let path = Path::new("people").join(format!("{github_username}.toml"));
+    let Some(person) = data.person(github_username) else {
+        if path.is_file() {
+            bail!(
+                "{} doesn't declare `github = \"{github_username}\"`, please ensure that the name matches",
+                path.display()
+            );
+        }
+        bail!("unknown person `{github_username}`");
+    };
+    if !path.is_file() {
+        bail!(
+            "person `{github_username}` must be defined in {}",
+            path.display()
+        );
+    }
+
+    let mut errors = Vec::new();
+    for check in PRELIMINARY_CHECKS {
+        (check.f)(data, &mut errors);
+    }
+    if !errors.is_empty() {
+        for err in &errors {
+            error!("validation error: {}", err);
+        }
+        bail!("{} validation errors found", errors.len());
+    }
+    for check in PERSON_CHECKS {
+        (check.f)(data, Some(person), &mut errors);
+    }
+    let mut warnings = Vec::new();
+    for check in PERSON_WARNING_CHECKS {
+        (check.f)(data, Some(person), &mut warnings);
+    }
+
+    let github = GitHubApi::new();
+    if let Err(err) = github.require_auth() {
+        warn!("couldn't check the GitHub username, as the GitHub API isn't available");
+        warn!("cause: {}", err);
+    } else {
+        match github.usernames(&[person.github_id()]) {
+            Ok(res) => match res.get(&person.github_id()) {
+                Some(name) if name != person.github() => errors.push(format!(
+                    "GitHub user `{}` changed username to `{}`",
+                    person.github(),
+                    name
+                )),
+                Some(_) => {}
+                None => errors.push(format!(
+                    "person `{}` has the GitHub id {}, which doesn't belong to any GitHub user",
+                    person.github(),
+                    person.github_id()
+                )),
+            },
+            Err(err) => errors.push(format!("couldn't verify the GitHub username: {}", err)),
+        }
+    }
+
+    for warning in &warnings {
+        warn!("validation warning: {}", warning);
+    }
+
+    if !errors.is_empty() {
+        for err in &errors {
+            error!("validation error: {}", err);
+        }
+        bail!("{} validation errors found", errors.len());
+    }
+
+    Ok(())
+}
+
+/// Whether a person is selected by the (optional) filter of the person checks.
+fn is_selected(only: Option<&Person>, github: &str) -> bool {
+    only.is_none_or(|person| person.github() == github)
+}
+
 /// Ensure working group names start with `wg-`
 fn validate_name_prefixes(data: &Data, errors: &mut Vec<String>) {
+    wrapper(data.teams(), errors, |team, _| team_name_prefixes(team));
+}
+
+fn team_name_prefixes(team: &Team) -> Result<(), Error> {
     fn ensure_prefix(
         team: &Team,
         kind: TeamKind,
@@ -154,16 +484,14 @@ fn validate_name_prefixes(data: &Data, errors: &mut Vec<String>) {
         }
         Ok(())
     }
-    wrapper(data.teams(), errors, |team, _| {
-        ensure_prefix(team, TeamKind::WorkingGroup, "wg-", &["wg-leads"])?;
-        ensure_prefix(
-            team,
-            TeamKind::ProjectGroup,
-            "project-",
-            &["project-group-leads"],
-        )?;
-        Ok(())
-    });
+    ensure_prefix(team, TeamKind::WorkingGroup, "wg-", &["wg-leads"])?;
+    ensure_prefix(
+        team,
+        TeamKind::ProjectGroup,
+        "project-",
+        &["project-group-leads"],
+    )?;
+    Ok(())
 }
 
 /// Ensure `subteam-of` points to an existing team
@@ -194,6 +522,61 @@ fn validate_subteam_of(data: &Data, errors: &mut Vec<String>) {
     });
 }
 
+/// Ensure teams don't include their own members through other teams
+fn validate_member_inclusions(data: &Data, errors: &mut Vec<String>) {
+    // The teams whose members are included in the members of the team.
+    fn inclusions<'a>(data: &'a Data, team: &'a Team) -> Vec<&'a Team> {
+        let people = team.raw_people();
+        let mut included: Vec<_> = people
+            .included_teams
+            .iter()
+            .filter_map(|name| data.team(name))
+            .collect();
+        if people.include_all_team_members {
+            included.extend(data.teams().filter(|other| {
+                other.kind() == TeamKind::Team
+                    && other.name() != team.name()
+                    && !other.is_alumni_team()
+            }));
+        }
+        included
+    }
+
+    fn find_cycle<'a>(
+        data: &'a Data,
+        team: &'a Team,
+        path: &mut Vec<&'a str>,
+    ) -> Option<Vec<&'a str>> {
+        if let Some(start) = path.iter().position(|name| *name == team.name()) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(team.name());
+            return Some(cycle);
+        }
+        path.push(team.name());
+        for included in inclusions(data, team) {
+            if let Some(cycle) = find_cycle(data, included, path) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        None
+    }
+
+    wrapper(data.teams(), errors, |team, _| {
+        if let Some(cycle) = find_cycle(data, team, &mut Vec::new()) {
+            // Report each cycle only once, from the team with the smallest name.
+            if cycle[0] == team.name() && cycle.iter().all(|name| *name >= team.name()) {
+                bail!(
+                    "team `{}` includes its own members: {}",
+                    team.name(),
+                    cycle.join(" => "),
+                );
+            }
+        }
+        Ok(())
+    });
+}
+
 /// Ensure team leaders are part of the teams they lead
 fn validate_team_leads(data: &Data, errors: &mut Vec<String>) {
     wrapper(data.teams(), errors, |team, errors| {
@@ -212,6 +595,59 @@ fn validate_team_leads(data: &Data, errors: &mut Vec<String>) {
     });
 }
 
+/// Ensure the teams whose leads are included in other teams have valid leads
+fn validate_included_leads(data: &Data, errors: &mut Vec<String>) {
+    // source team -> teams including its leads
+    let mut included_in: HashMap<&str, Vec<&str>> = HashMap::new();
+    for team in data.teams() {
+        let people = team.raw_people();
+        let mut kinds = Vec::new();
+        if people.include_team_leads {
+            kinds.push(TeamKind::Team);
+        }
+        if people.include_wg_leads {
+            kinds.push(TeamKind::WorkingGroup);
+        }
+        if people.include_project_group_leads {
+            kinds.push(TeamKind::ProjectGroup);
+        }
+        for source in data
+            .teams()
+            .filter(|source| source.name() != team.name() && kinds.contains(&source.kind()))
+        {
+            included_in
+                .entry(source.name())
+                .or_default()
+                .push(team.name());
+        }
+    }
+
+    let sources = data
+        .teams()
+        .filter(|source| included_in.contains_key(source.name()));
+    wrapper(sources, errors, |source, _| {
+        let members = source.members(data)?;
+        for lead in source.leads() {
+            if !members.contains(lead) || data.person(lead).is_none() {
+                let mut teams = included_in[source.name()].clone();
+                teams.sort_unstable();
+                bail!(
+                    "the leads of team `{}` are included in {}, but its lead `{}` \
+                     is not a valid member of it",
+                    source.name(),
+                    teams
+                        .iter()
+                        .map(|team| format!("`{team}`"))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    lead
+                );
+            }
+        }
+        Ok(())
+    });
+}
+
 /// Ensure team members are people
 fn validate_team_members(data: &Data, errors: &mut Vec<String>) {
     wrapper(data.teams(), errors, |team, errors| {
@@ -229,8 +665,29 @@ fn validate_team_members(data: &Data, errors: &mut Vec<String>) {
     });
 }
 
-/// Alumni team must consist only of automatically populated alumni from the other teams
-fn validate_alumni(data: &Data, errors: &mut Vec<String>) {
+/// Report people who aren't members of any active team, to audit stale people files
+fn validate_unused_people(data: &Data, warnings: &mut Vec<String>) {
+    let active_members = match data.active_members() {
+        Ok(active_members) => active_members,
+        Err(err) => {
+            warnings.push(format!("could not get the active members: {err}"));
+            return;
+        }
+    };
+
+    wrapper(data.people(), warnings, |person, _| {
+        if !active_members.contains(person.github()) {
+            bail!(
+                "person `{}` is not a member of any active team",
+                person.github()
+            );
+        }
+        Ok(())
+    });
+}
+
+/// Alumni team must exist and consist only of automatically populated alumni from the other teams
+fn validate_alumni_team(data: &Data, errors: &mut Vec<String>) {
     let Some(alumni_team) = data.team("alumni") else {
         errors.push("cannot find an 'alumni' team".to_owned());
         return;
@@ -238,52 +695,79 @@ fn validate_alumni(data: &Data, errors: &mut Vec<String>) {
     if !alumni_team.explicit_members().is_empty() {
         errors.push("'alumni' team must not have explicit members; move them to the appropriate team's alumni entry".to_owned());
     }
+    if !alumni_team.raw_people().include_all_alumni {
+        errors.push("'alumni' team must set `include-all-alumni = true`, otherwise it doesn't contain the alumni of the other teams".to_owned());
+    }
+}
 
-    // Teams must contain an `alumni = […]` field (even if empty) so that there
-    // is an obvious place to move contributors within the same file when
-    // removing from `members`.
-    //
-    // Marker teams are exempt from this, as well as teams which comprise only
-    // members of other teams via `include-team-leads` or similar; they do not
-    // need `alumni = […]`. For these teams, the correct place to put alumni is
-    // in the same team they're being included from.
+/// Ensure teams including everyone of a kind don't combine it with other membership settings
+fn validate_inclusion_settings(data: &Data, errors: &mut Vec<String>) {
     wrapper(data.teams(), errors, |team, _| {
-        // Exhaustive destructuring to ensure this code is touched if a new
-        // "include" settings is introduced.
-        let TeamPeople {
-            leads: _,
-            members,
-            alumni,
-            included_teams,
-            include_team_leads,
-            include_wg_leads,
-            include_project_group_leads,
-            include_all_team_members,
-            include_all_alumni,
-        } = team.raw_people();
-
-        if alumni.is_none() {
-            let exempt_team_kind = match team.kind() {
-                TeamKind::MarkerTeam => true,
-                TeamKind::Team | TeamKind::WorkingGroup | TeamKind::ProjectGroup => false,
-            };
-            let exempt_composition = members.is_empty() // intentionally not team.members(data).is_empty()
-                && (*include_team_leads
-                    || *include_wg_leads
-                    || *include_project_group_leads
-                    || *include_all_team_members
-                    || *include_all_alumni
-                    || !included_teams.is_empty());
-            let exempt = exempt_team_kind || exempt_composition;
-            if !exempt {
-                let team_name = team.name();
-                bail!("team '{team_name}' needs an `alumni = []` entry");
+        let people = team.raw_people();
+        let mut include_all = Vec::new();
+        if people.include_all_team_members {
+            include_all.push("`include-all-team-members`");
+        }
+        if people.include_all_alumni {
+            include_all.push("`include-all-alumni`");
+        }
+        if include_all.len() > 1 {
+            bail!(
+                "team `{}` sets both {}, which are mutually exclusive",
+                team.name(),
+                include_all.join(" and ")
+            );
+        }
+        if let Some(setting) = include_all.first() {
+            if !people.members.is_empty() {
+                bail!(
+                    "team `{}` sets {} but also lists explicit members, which are mutually exclusive",
+                    team.name(),
+                    setting
+                );
             }
         }
         Ok(())
     });
 }
 
+/// Teams must have an alumni entry where their former members can be moved to
+fn validate_alumni(data: &Data, errors: &mut Vec<String>) {
+    wrapper(data.teams(), errors, |team, _| {
+        if team.raw_people().alumni.is_none() && team.requires_alumni_entry() {
+            let team_name = team.name();
+            bail!("team '{team_name}' needs an `alumni = []` entry");
+        }
+        Ok(())
+    });
+}
+
+/// Ensure the explicit members of teams are sorted, if enabled in the configuration
+fn validate_sorted_members(data: &Data, errors: &mut Vec<String>) {
+    let config = data.config();
+    if !config.require_sorted_members() {
+        return;
+    }
+    let teams = data
+        .teams()
+        .filter(|team| !config.unsorted_members_teams().contains(team.name()));
+    wrapper(teams, errors, |team, _| {
+        let members = team.explicit_members();
+        if let Some(pair) = members
+            .windows(2)
+            .find(|pair| pair[0].github.to_lowercase() > pair[1].github.to_lowercase())
+        {
+            bail!(
+                "the members of team `{}` are not sorted: `{}` should come before `{}`",
+                team.name(),
+                pair[1].github,
+                pair[0].github
+            );
+        }
+        Ok(())
+    });
+}
+
 fn validate_archived_teams(data: &Data, errors: &mut Vec<String>) {
     wrapper(data.archived_teams(), errors, |team, _| {
         if !team.members(data)?.is_empty() {
@@ -293,8 +777,41 @@ fn validate_archived_teams(data: &Data, errors: &mut Vec<String>) {
     })
 }
 
+/// Warn about marker teams with side effects, as they are meant to be pure labels
+fn validate_marker_teams(data: &Data, errors: &mut Vec<String>) {
+    let marker_teams = data
+        .teams()
+        .filter(|team| team.kind() == TeamKind::MarkerTeam);
+    wrapper(marker_teams, errors, |team, _| {
+        let mut sections = Vec::new();
+        if !team.github_teams(data)?.is_empty() {
+            sections.push("`github` teams");
+        }
+        if !team.raw_lists().is_empty() {
+            sections.push("`lists`");
+        }
+        if team.permissions().has_any() || team.leads_permissions().has_any() {
+            sections.push("`permissions`");
+        }
+        if data
+            .repos()
+            .any(|repo| repo.access.teams.contains_key(team.name()))
+        {
+            sections.push("repo access");
+        }
+        if !sections.is_empty() {
+            bail!(
+                "marker team '{}' has {}, but marker teams are labels and shouldn't have side effects",
+                team.name(),
+                sections.join(", ")
+            );
+        }
+        Ok(())
+    });
+}
+
 /// Ensure every person is part of at least one team (active or archived)
-fn validate_inactive_members(data: &Data, errors: &mut Vec<String>) {
+fn validate_inactive_members(data: &Data, only: Option<&Person>, errors: &mut Vec<String>) {
     let mut referenced_members = HashSet::new();
     wrapper(
         data.teams().chain(data.archived_teams()),
@@ -341,7 +858,9 @@ fn validate_inactive_members(data: &Data, errors: &mut Vec<String>) {
         })
         .collect::<HashSet<_>>();
     wrapper(
-        all_members.difference(&referenced_members),
+        all_members
+            .difference(&referenced_members)
+            .filter(|person| is_selected(only, person)),
         errors,
         |person, _| {
             if !data.person(person).unwrap().permissions().has_any()
@@ -360,13 +879,15 @@ fn validate_inactive_members(data: &Data, errors: &mut Vec<String>) {
 }
 
 /// Ensure every member of a team with a mailing list has an email address
-fn validate_list_email_addresses(data: &Data, errors: &mut Vec<String>) {
+fn validate_list_email_addresses(data: &Data, only: Option<&Person>, errors: &mut Vec<String>) {
     wrapper(data.teams(), errors, |team, errors| {
         let lists = team.lists(data)?;
         if lists.is_empty() {
             return Ok(());
         }
-        wrapper(team.members(data)?.iter(), errors, |member, _| {
+        let members = team.members(data)?;
+        let members = members.iter().filter(|member| is_selected(only, member));
+        wrapper(members, errors, |member, _| {
             if let Some(member) = data.person(member) {
                 if let Email::Missing = member.email() {
                     bail!(
@@ -382,6 +903,26 @@ fn validate_list_email_addresses(data: &Data, errors: &mut Vec<String>) {
     });
 }
 
+/// Ensure every member of any team has an email address
+fn validate_team_email_addresses(data: &Data, errors: &mut Vec<String>) {
+    wrapper(data.teams(), errors, |team, _| {
+        let mut missing = team
+            .members(data)?
+            .into_iter()
+            .filter(|member| matches!(data.person(member).map(|p| p.email()), Some(Email::Missing)))
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            missing.sort_unstable();
+            bail!(
+                "the following members of team `{}` have no email address: {}",
+                team.name(),
+                missing.join(", "),
+            );
+        }
+        Ok(())
+    });
+}
+
 /// Ensure members of extra-people in a list are real people
 fn validate_list_extra_people(data: &Data, errors: &mut Vec<String>) {
     wrapper(data.teams(), errors, |team, errors| {
@@ -440,8 +981,9 @@ fn validate_list_addresses(data: &Data, errors: &mut Vec<String>) {
 }
 
 /// Ensure people email addresses are correct
-fn validate_people_addresses(data: &Data, errors: &mut Vec<String>) {
-    wrapper(data.people(), errors, |person, _| {
+fn validate_people_addresses(data: &Data, only: Option<&Person>, errors: &mut Vec<String>) {
+    let people = data.people().filter(|p| is_selected(only, p.github()));
+    wrapper(people, errors, |person, _| {
         if let Email::Present(email) = person.email() {
             if !email.contains('@') {
                 bail!("invalid email address of `{}`: {}", person.github(), email);
@@ -451,30 +993,109 @@ fn validate_people_addresses(data: &Data, errors: &mut Vec<String>) {
     });
 }
 
-/// Ensure members of teams with permissions don't explicitly have those permissions
-fn validate_duplicate_permissions(data: &Data, errors: &mut Vec<String>) {
-    wrapper(data.teams(), errors, |team, errors| {
-        wrapper(team.members(data)?.iter(), errors, |member, _| {
-            if let Some(person) = data.person(member) {
-                for permission in &Permissions::available(data.config()) {
-                    if team.permissions().has(permission)
-                        && person.permissions().has_directly(permission)
-                    {
-                        bail!(
-                            "user `{}` has the permission `{}` both explicitly and through \
-                             the `{}` team",
-                            member,
-                            permission,
-                            team.name()
-                        );
-                    }
-                }
-            }
-            Ok(())
-        });
-        Ok(())
-    });
-}
+/// Warn about people sharing the same display name
+fn validate_duplicate_names(data: &Data, errors: &mut Vec<String>) {
+    let mut names: HashMap<&str, Vec<&str>> = HashMap::new();
+    for person in data.people() {
+        names
+            .entry(person.name())
+            .or_default()
+            .push(person.github());
+    }
+    wrapper(names.into_iter(), errors, |(name, mut people), _| {
+        if people.len() > 1 {
+            people.sort_unstable();
+            bail!(
+                "the name `{}` is shared by multiple people: {}",
+                name,
+                people.join(", ")
+            );
+        }
+        Ok(())
+    });
+}
+
+/// Warn about people using email addresses that can't receive emails
+fn validate_placeholder_emails(data: &Data, only: Option<&Person>, errors: &mut Vec<String>) {
+    let placeholders = data.config().placeholder_email_domains();
+    let people = data.people().filter(|p| is_selected(only, p.github()));
+    wrapper(people, errors, |person, _| {
+        if let Email::Present(email) = person.email() {
+            let Some((_, domain)) = email.rsplit_once('@') else {
+                return Ok(());
+            };
+            let domain = domain.to_lowercase();
+            let is_placeholder = placeholders.iter().any(|placeholder| {
+                domain == *placeholder || domain.ends_with(&format!(".{placeholder}"))
+            });
+            if is_placeholder {
+                bail!(
+                    "the email address of `{}` uses the placeholder domain `{}`",
+                    person.github(),
+                    domain
+                );
+            }
+        }
+        Ok(())
+    });
+}
+
+/// Report people with a rust-lang.org email address that isn't part of any
+/// mailing list, to audit stale aliases
+fn validate_unlisted_rust_lang_emails(data: &Data, warnings: &mut Vec<String>) {
+    let lists = match data.lists() {
+        Ok(lists) => lists,
+        Err(err) => {
+            warnings.push(format!("could not get all the mailing lists: {err}"));
+            return;
+        }
+    };
+    let listed: HashSet<_> = lists
+        .values()
+        .flat_map(|list| list.emails().iter().map(|email| email.as_str()))
+        .chain(lists.keys().map(|address| address.as_str()))
+        .map(|email| email.to_lowercase())
+        .collect();
+
+    wrapper(data.people(), warnings, |person, _| {
+        if let Email::Present(email) = person.email() {
+            let email = email.to_lowercase();
+            if email.ends_with("@rust-lang.org") && !listed.contains(&email) {
+                bail!(
+                    "the email address `{}` of `{}` isn't part of any mailing list",
+                    email,
+                    person.github()
+                );
+            }
+        }
+        Ok(())
+    });
+}
+
+/// Ensure members of teams with permissions don't explicitly have those permissions
+fn validate_duplicate_permissions(data: &Data, errors: &mut Vec<String>) {
+    wrapper(data.teams(), errors, |team, errors| {
+        wrapper(team.members(data)?.iter(), errors, |member, _| {
+            if let Some(person) = data.person(member) {
+                for permission in &Permissions::available(data.config()) {
+                    if team.permissions().has(permission)
+                        && person.permissions().has_directly(permission)
+                    {
+                        bail!(
+                            "user `{}` has the permission `{}` both explicitly and through \
+                             the `{}` team",
+                            member,
+                            permission,
+                            team.name()
+                        );
+                    }
+                }
+            }
+            Ok(())
+        });
+        Ok(())
+    });
+}
 
 /// Ensure the permissions are valid
 fn validate_permissions(data: &Data, errors: &mut Vec<String>) {
@@ -534,22 +1155,44 @@ fn validate_rfcbot_exclude_members(data: &Data, errors: &mut Vec<String>) {
     });
 }
 
-/// Ensure team names are alphanumeric + `-`
-fn validate_team_names(data: &Data, errors: &mut Vec<String>) {
+/// Ensure teams using rfcbot have at least one member taking part in FCPs
+fn validate_rfcbot_voters(data: &Data, errors: &mut Vec<String>) {
     wrapper(data.teams(), errors, |team, _| {
-        if !ascii_kebab_case(team.name()) {
-            bail!(
-                "team name `{}` can only be alphanumeric with hyphens",
-                team.name()
-            );
+        if let Some(rfcbot) = team.rfcbot_data() {
+            let members = team.members(data)?;
+            if members
+                .iter()
+                .all(|member| rfcbot.exclude_members.iter().any(|m| m == member))
+            {
+                bail!(
+                    "team `{}` uses rfcbot, but none of its members take part in FCPs",
+                    team.name()
+                );
+            }
         }
         Ok(())
     });
 }
 
+/// Ensure team names are alphanumeric + `-`
+fn validate_team_names(data: &Data, errors: &mut Vec<String>) {
+    wrapper(data.teams(), errors, |team, _| team_name_kebab_case(team));
+}
+
+fn team_name_kebab_case(team: &Team) -> Result<(), Error> {
+    if !ascii_kebab_case(team.name()) {
+        bail!(
+            "team name `{}` can only be alphanumeric with hyphens",
+            team.name()
+        );
+    }
+    Ok(())
+}
+
 /// Ensure GitHub teams are unique and in the allowed orgs
 fn validate_github_teams(data: &Data, errors: &mut Vec<String>) {
     let mut found = HashMap::new();
+    let mut parents = HashMap::new();
     let allowed = data.config().allowed_github_orgs();
     wrapper(data.teams(), errors, |team, errors| {
         wrapper(
@@ -563,6 +1206,24 @@ fn validate_github_teams(data: &Data, errors: &mut Vec<String>) {
                         team.name()
                     );
                 }
+                for maintainer in gh_team.maintainers {
+                    if !gh_team
+                        .members
+                        .iter()
+                        .any(|(github, _)| github == maintainer)
+                    {
+                        bail!(
+                            "`{}` is a maintainer of the GitHub team `{}/{}` (in team `{}`), but not a member of it",
+                            maintainer,
+                            gh_team.org,
+                            gh_team.name,
+                            team.name()
+                        );
+                    }
+                }
+                if let Some(parent) = gh_team.parent {
+                    parents.insert((gh_team.org, gh_team.name), parent);
+                }
                 if let Some(other) = found.insert((gh_team.org, gh_team.name), team.name()) {
                     bail!(
                         "GitHub team `{}/{}` is defined for both the `{}` and `{}` teams",
@@ -577,19 +1238,87 @@ fn validate_github_teams(data: &Data, errors: &mut Vec<String>) {
         );
         Ok(())
     });
+
+    let mut parents = parents.into_iter().collect::<Vec<_>>();
+    parents.sort_unstable();
+    wrapper(parents.iter(), errors, |((org, name), parent), _| {
+        if !found.contains_key(&(*org, *parent)) {
+            bail!(
+                "the parent of the GitHub team `{org}/{name}` is `{parent}`, which isn't a GitHub team defined in the `{org}` org",
+            );
+        }
+        // Follow the chain of parents, which must not lead back to the team.
+        // Cycles further up the chain are reported for the teams in them, by
+        // the first one of them in alphabetical order.
+        let mut chain = vec![*name];
+        let mut current = *parent;
+        while !chain.contains(&current) {
+            chain.push(current);
+            match parents.iter().find(|((o, n), _)| o == org && *n == current) {
+                Some((_, next)) => current = next,
+                None => return Ok(()),
+            }
+        }
+        if current == *name && chain.iter().all(|team| team >= name) {
+            chain.push(current);
+            bail!(
+                "the GitHub team `{org}/{name}` is nested under itself ({})",
+                chain.join(" -> ")
+            );
+        }
+        Ok(())
+    });
+}
+
+/// Ensure GitHub teams have members once all the inclusions are resolved
+fn validate_nonempty_github_teams(data: &Data, errors: &mut Vec<String>) {
+    let allowed_empty = data.config().empty_github_teams();
+    let teams = data
+        .teams()
+        .filter(|team| !allowed_empty.contains(team.name()));
+    wrapper(teams, errors, |team, errors| {
+        wrapper(
+            team.github_teams(data)?.into_iter(),
+            errors,
+            |gh_team, _| {
+                if gh_team.members.is_empty() {
+                    bail!(
+                        "GitHub team `{}/{}` (in team `{}`) has no members",
+                        gh_team.org,
+                        gh_team.name,
+                        team.name()
+                    );
+                }
+                Ok(())
+            },
+        );
+        Ok(())
+    });
 }
 
-/// Ensure there are no misspelled GitHub account names
+/// Ensure there are no misspelled GitHub account names, and that the GitHub
+/// ids of people belong to existing GitHub users
 fn validate_github_usernames(data: &Data, github: &GitHubApi, errors: &mut Vec<String>) {
-    let people = data
-        .people()
-        .map(|p| (p.github_id(), p))
-        .collect::<HashMap<_, _>>();
-    match github.usernames(&people.keys().cloned().collect::<Vec<_>>()) {
-        Ok(res) => wrapper(res.iter(), errors, |(id, name), _| {
-            let original = people[id].github();
-            if original != name {
-                bail!("GitHub user `{}` changed username to `{}`", original, name);
+    let mut ids = data.people().map(|p| p.github_id()).collect::<Vec<_>>();
+    ids.sort_unstable();
+    ids.dedup();
+    match github.usernames(&ids) {
+        Ok(res) => wrapper(data.people(), errors, |person, _| {
+            match res.get(&person.github_id()) {
+                Some(name) if name != person.github() => {
+                    bail!(
+                        "GitHub user `{}` changed username to `{}`",
+                        person.github(),
+                        name
+                    );
+                }
+                Some(_) => {}
+                None => bail!(
+                    "person `{}` has the GitHub id {}, which doesn't belong to any GitHub user; \
+                     check if the user has possibly deleted their account",
+                    person.github(),
+                    person.github_id()
+                ),
             }
             Ok(())
         }),
@@ -597,6 +1326,92 @@ fn validate_github_usernames(data: &Data, github: &GitHubApi, errors: &mut Vec<S
     }
 }
 
+/// Ensure no two people share the same GitHub id
+fn validate_unique_github_ids(data: &Data, errors: &mut Vec<String>) {
+    let mut people = data.people().collect::<Vec<_>>();
+    people.sort_by_key(|person| person.github());
+    let mut ids = HashMap::new();
+    wrapper(people.into_iter(), errors, |person, _| {
+        if let Some(other) = ids.insert(person.github_id(), person.github()) {
+            bail!(
+                "people `{}` and `{}` have the same GitHub id {}",
+                other,
+                person.github(),
+                person.github_id()
+            );
+        }
+        Ok(())
+    });
+}
+
+/// Warn about required CI checks that never ran on the protected branch, as
+/// they would permanently block merges. Only branch protections matching a
+/// single branch can be checked.
+fn validate_branch_protection_ci_contexts(
+    data: &Data,
+    github: &GitHubApi,
+    errors: &mut Vec<String>,
+) {
+    // Never query organizations we don't manage; repos in other organizations
+    // are already reported by `validate_repos`.
+    let allowed_orgs = data.config().allowed_github_orgs();
+    let repos = data.repos().filter(|repo| allowed_orgs.contains(&repo.org));
+    wrapper(repos, errors, |repo, errors| {
+        let protections = repo.branch_protections.iter().filter(|protection| {
+            !protection.ci_checks.is_empty() && !protection.pattern.contains(['*', '?', '['])
+        });
+        wrapper(protections, errors, |protection, errors| {
+            let contexts = match github.ci_contexts(&repo.org, &repo.name, &protection.pattern) {
+                Ok(contexts) => contexts,
+                Err(err) => bail!(
+                    "couldn't fetch the CI checks of branch '{}' in repo '{}/{}': {}",
+                    protection.pattern,
+                    repo.org,
+                    repo.name,
+                    err
+                ),
+            };
+            let missing = protection
+                .ci_checks
+                .iter()
+                .filter(|check| !contexts.contains(*check));
+            wrapper(missing, errors, |check, _| {
+                bail!(
+                    "repo '{}/{}' requires the CI check '{}' in the branch protection for {}, \
+                     but it didn't run on the latest commit of that branch",
+                    repo.org,
+                    repo.name,
+                    check,
+                    protection.pattern,
+                );
+            });
+            Ok(())
+        });
+        Ok(())
+    });
+}
+
+/// Ensure the members of GitHub teams map back to people in the repo
+fn validate_github_team_members(data: &Data, errors: &mut Vec<String>) {
+    wrapper(data.teams(), errors, |team, errors| {
+        wrapper(
+            team.github_teams(data)?.into_iter(),
+            errors,
+            |gh_team, errors| {
+                wrapper(gh_team.unknown_members.iter(), errors, |name, _| {
+                    bail!(
+                        "GitHub team `{}/{}` contains `{name}`, who isn't a person in the repo",
+                        gh_team.org,
+                        gh_team.name,
+                    );
+                });
+                Ok(())
+            },
+        );
+        Ok(())
+    });
+}
+
 /// Ensure the user doens't put an URL as the Zulip stream name.
 fn validate_zulip_stream_name(data: &Data, errors: &mut Vec<String>) {
     wrapper(data.teams(), errors, |team, _| {
@@ -645,16 +1460,21 @@ fn validate_subteam_of_required(data: &Data, errors: &mut Vec<String>) {
     })
 }
 
-fn validate_discord_team_members_have_discord_ids(data: &Data, errors: &mut Vec<String>) {
+fn validate_discord_team_members_have_discord_ids(
+    data: &Data,
+    only: Option<&Person>,
+    errors: &mut Vec<String>,
+) {
     wrapper(data.teams(), errors, |team, _| {
         if team.discord_roles().is_some() && team.name() != "all" {
-            let team_members = team.members(data)?;
-            if team_members.len() != team.discord_ids(data)?.len() {
-                let missing_discord_id = team_members
-                    .into_iter()
-                    .filter(|name| data.person(name).map(|p| p.discord_id()) == Some(None))
-                    .collect::<Vec<_>>();
-
+            let mut missing_discord_id = team
+                .members(data)?
+                .into_iter()
+                .filter(|name| is_selected(only, name))
+                .filter(|name| data.person(name).map(|p| p.discord_id()) == Some(None))
+                .collect::<Vec<_>>();
+            if !missing_discord_id.is_empty() {
+                missing_discord_id.sort_unstable();
                 bail!(
                     "the following members of the \"{}\" team do not have discord_ids: {}",
                     team.name(),
@@ -667,6 +1487,23 @@ fn validate_discord_team_members_have_discord_ids(data: &Data, errors: &mut Vec<
     });
 }
 
+/// Warn about teams declaring Discord roles that no member could be given,
+/// as none of them has a Discord id
+fn validate_discord_roles_have_members(data: &Data, errors: &mut Vec<String>) {
+    wrapper(data.teams(), errors, |team, _| {
+        if team.discord_roles().is_some()
+            && team.name() != "all"
+            && team.discord_ids(data)?.is_empty()
+        {
+            bail!(
+                "the \"{}\" team declares Discord roles, but none of its members has a discord_id",
+                team.name()
+            );
+        }
+        Ok(())
+    });
+}
+
 /// Ensure every member of a team that has a Zulip group has a Zulip id
 fn validate_zulip_users(data: &Data, zulip: &ZulipApi, errors: &mut Vec<String>) {
     let by_id = match zulip.get_users() {
@@ -777,10 +1614,35 @@ fn validate_zulip_group_extra_people(data: &Data, errors: &mut Vec<String>) {
     });
 }
 
+/// Ensure repo descriptions are accepted by GitHub
+fn validate_repo_description_length(data: &Data, errors: &mut Vec<String>) {
+    wrapper(data.all_repos(), errors, |repo, _| {
+        let len = repo.description.chars().count();
+        if len > MAX_REPO_DESCRIPTION_LEN {
+            bail!(
+                "The description of the repo {}/{} is {} characters long, but GitHub allows at most {}",
+                repo.org,
+                repo.name,
+                len,
+                MAX_REPO_DESCRIPTION_LEN
+            );
+        }
+        Ok(())
+    });
+}
+
+/// The maximum length of a repository description allowed by GitHub.
+const MAX_REPO_DESCRIPTION_LEN: usize = 350;
+/// The maximum number of topics GitHub allows on a repository.
+const MAX_REPO_TOPICS: usize = 20;
+/// The maximum length of a single repository topic allowed by GitHub.
+const MAX_REPO_TOPIC_LEN: usize = 50;
+
 /// Ensure repos reference valid teams and that they are unique
 fn validate_repos(data: &Data, errors: &mut Vec<String>) {
     let allowed_orgs = data.config().allowed_github_orgs();
     let github_teams = data.github_teams();
+    let archived_teams: HashSet<_> = data.archived_teams().map(|team| team.name()).collect();
     let mut repo_map = HashSet::new();
 
     wrapper(data.all_repos(), errors, |repo, _| {
@@ -796,6 +1658,14 @@ fn validate_repos(data: &Data, errors: &mut Vec<String>) {
             );
         }
         for team_name in repo.access.teams.keys() {
+            if archived_teams.contains(team_name.as_str()) {
+                bail!(
+                    "access for {}/{} is invalid: '{}' is an archived team, which shouldn't hold repo access",
+                    repo.org,
+                    repo.name,
+                    team_name
+                );
+            }
             if !github_teams.contains(&(repo.org.clone(), team_name.clone())) {
                 bail!(
                         "access for {}/{} is invalid: '{}' is not configured as a GitHub team for the '{}' org",
@@ -821,6 +1691,218 @@ fn validate_repos(data: &Data, errors: &mut Vec<String>) {
     });
 }
 
+/// Ensure repo topics are accepted by GitHub
+fn validate_repo_topics(data: &Data, errors: &mut Vec<String>) {
+    wrapper(data.all_repos(), errors, |repo, errors| {
+        if repo.topics.len() > MAX_REPO_TOPICS {
+            bail!(
+                "The repo {}/{} has {} topics, but GitHub allows at most {}",
+                repo.org,
+                repo.name,
+                repo.topics.len(),
+                MAX_REPO_TOPICS
+            );
+        }
+        wrapper(repo.topics.iter(), errors, |topic, _| {
+            if topic.is_empty()
+                || topic.len() > MAX_REPO_TOPIC_LEN
+                || topic.starts_with('-')
+                || !topic
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+            {
+                bail!(
+                    "The topic '{}' of the repo {}/{} is invalid: topics must start with a lowercase letter or number, consist of lowercase letters, numbers and hyphens, and be at most {} characters long",
+                    topic,
+                    repo.org,
+                    repo.name,
+                    MAX_REPO_TOPIC_LEN
+                );
+            }
+            Ok(())
+        });
+        Ok(())
+    });
+}
+
+/// Ensure the default branch of repos is a valid branch name
+fn validate_repo_default_branch(data: &Data, errors: &mut Vec<String>) {
+    wrapper(data.all_repos(), errors, |repo, _| {
+        if let Some(branch) = &repo.default_branch {
+            if branch.is_empty() || branch.contains(|c: char| c.is_whitespace() || c == '*') {
+                bail!(
+                    "The default branch of the repo {}/{} is not a valid branch name: '{}'",
+                    repo.org,
+                    repo.name,
+                    branch
+                );
+            }
+        }
+        Ok(())
+    });
+}
+
+/// Ensure repos allow at least one merge method
+fn validate_repo_merge_methods(data: &Data, errors: &mut Vec<String>) {
+    wrapper(data.all_repos(), errors, |repo, _| {
+        if repo.allow_squash_merge == Some(false)
+            && repo.allow_merge_commit == Some(false)
+            && repo.allow_rebase_merge == Some(false)
+        {
+            bail!(
+                "The repo {}/{} disallows all merge methods, but GitHub requires at least one to be allowed",
+                repo.org,
+                repo.name
+            );
+        }
+        Ok(())
+    });
+}
+
+/// Ensure autolinks have unique key prefixes and usable URL templates
+fn validate_repo_autolinks(data: &Data, errors: &mut Vec<String>) {
+    wrapper(data.all_repos(), errors, |repo, errors| {
+        let mut key_prefixes = HashSet::new();
+        wrapper(repo.autolinks.iter(), errors, |autolink, _| {
+            if !key_prefixes.insert(autolink.key_prefix.to_lowercase()) {
+                bail!(
+                    "The repo {}/{} has multiple autolinks with the key prefix '{}'",
+                    repo.org,
+                    repo.name,
+                    autolink.key_prefix
+                );
+            }
+            if !autolink.url_template.contains("<num>") {
+                bail!(
+                    "The URL template of the '{}' autolink of the repo {}/{} must contain `<num>`",
+                    autolink.key_prefix,
+                    repo.org,
+                    repo.name
+                );
+            }
+            Ok(())
+        });
+        Ok(())
+    });
+}
+
+/// Ensure repos list each bot once, and only use Renovate where it's installed
+fn validate_repo_bots(data: &Data, errors: &mut Vec<String>) {
+    let renovate_orgs = data.config().renovate_orgs();
+    wrapper(data.all_repos(), errors, |repo, _| {
+        let mut seen = Vec::new();
+        for bot in &repo.bots {
+            if seen.contains(&bot) {
+                bail!(
+                    "The repo {}/{} lists the bot `{}` multiple times",
+                    repo.org,
+                    repo.name,
+                    bot.github_username()
+                );
+            }
+            seen.push(bot);
+        }
+        if repo.bots.contains(&Bot::Renovate) && !renovate_orgs.contains(&repo.org) {
+            bail!(
+                "The repo {}/{} uses the Renovate bot, but the Renovate app is not installed in the '{}' org (see `renovate-orgs` in config.toml)",
+                repo.org,
+                repo.name,
+                repo.org
+            );
+        }
+        Ok(())
+    });
+}
+
+/// Warn about repos where nobody besides the org owners has admin access
+fn validate_repo_admins(data: &Data, errors: &mut Vec<String>) {
+    wrapper(data.repos(), errors, |repo, _| {
+        if repo.org_owners_admin_only {
+            return Ok(());
+        }
+        let has_admin = repo
+            .access
+            .teams
+            .values()
+            .chain(repo.access.individuals.values())
+            .any(|permission| matches!(permission, RepoPermission::Admin));
+        if !has_admin {
+            bail!(
+                "repo '{}/{}' has no team or individual with admin access; \
+                 set `org-owners-admin-only = true` if this is intentional",
+                repo.org,
+                repo.name
+            );
+        }
+        Ok(())
+    })
+}
+
+/// Warn about repo homepages that are not public absolute URLs
+fn validate_repo_homepages(data: &Data, errors: &mut Vec<String>) {
+    wrapper(data.repos(), errors, |repo, _| {
+        // An empty homepage explicitly removes the homepage of the repo.
+        let Some(homepage) = repo.homepage.as_ref().filter(|h| !h.is_empty()) else {
+            return Ok(());
+        };
+        let Some(rest) = homepage
+            .strip_prefix("https://")
+            .or_else(|| homepage.strip_prefix("http://"))
+        else {
+            bail!(
+                "repo '{}/{}' has a homepage that is not an absolute http(s) URL: {}",
+                repo.org,
+                repo.name,
+                homepage
+            );
+        };
+        let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+        let host = host.rsplit_once(':').map_or(host, |(host, _port)| host);
+        let is_internal = host.is_empty()
+            || host == "localhost"
+            || host.starts_with("127.")
+            || host.ends_with(".localhost")
+            || host.ends_with(".local")
+            || host.ends_with(".internal")
+            || host.ends_with(".invalid");
+        if is_internal {
+            bail!(
+                "repo '{}/{}' has a homepage pointing to an internal host: {}",
+                repo.org,
+                repo.name,
+                homepage
+            );
+        }
+        Ok(())
+    })
+}
+
+/// Warn about repos giving access to individuals instead of teams (as per
+/// RFC 2872), unless they explicitly allow it
+fn validate_individual_access(data: &Data, errors: &mut Vec<String>) {
+    wrapper(data.repos(), errors, |repo, _| {
+        if !repo.allow_individual_access && !repo.access.individuals.is_empty() {
+            let mut individuals: Vec<_> = repo.access.individuals.keys().collect();
+            individuals.sort_unstable();
+            bail!(
+                "repo '{}/{}' gives access to individuals ({}), access should be given through teams \
+                 or the repo should set `allow-individual-access = true`",
+                repo.org,
+                repo.name,
+                individuals
+                    .iter()
+                    .map(|name| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        Ok(())
+    })
+}
+
+/// The maximum number of approvals GitHub allows requiring in a branch protection.
+const MAX_REQUIRED_APPROVALS: u32 = 6;
+
 /// Validate that branch protections make sense in combination with used bots.
 fn validate_branch_protections(data: &Data, errors: &mut Vec<String>) {
     let github_teams = data.github_teams();
@@ -840,6 +1922,58 @@ but that team does not seem to exist"#,
                         team
                     );
                 }
+                if repo
+                    .access
+                    .teams
+                    .get(team)
+                    .is_none_or(|permission| *permission < RepoPermission::Write)
+                {
+                    bail!(
+                        r#"repo '{}' uses a branch protection for {} that allows the '{}' github team to merge;
+but that team doesn't have at least write access to the repo"#,
+                        repo.name,
+                        protection.pattern,
+                        team
+                    );
+                }
+            }
+
+            if let Some(required_approvals) = protection.required_approvals {
+                if required_approvals > MAX_REQUIRED_APPROVALS {
+                    bail!(
+                        r#"repo '{}' uses a branch protection for {} that requires {} approvals, but at most {} approvals can be required"#,
+                        repo.name,
+                        protection.pattern,
+                        required_approvals,
+                        MAX_REQUIRED_APPROVALS,
+                    );
+                }
+            }
+
+            for actor in &protection.bypass_actors {
+                match actor {
+                    BypassActor::Team(team) => {
+                        if !github_teams.contains(&(repo.org.clone(), team.clone())) {
+                            bail!(
+                                r#"repo '{}' uses a branch protection for {} that can be bypassed by the '{}' github team;
+but that team does not seem to exist"#,
+                                repo.name,
+                                protection.pattern,
+                                team
+                            );
+                        }
+                    }
+                    BypassActor::User(user) => {
+                        if data.person(user).is_none() {
+                            bail!(
+                                r#"repo '{}' uses a branch protection for {} that can be bypassed by '{}', who isn't a person in the repo"#,
+                                repo.name,
+                                protection.pattern,
+                                user
+                            );
+                        }
+                    }
+                }
             }
 
             if !protection.pr_required {
@@ -859,6 +1993,20 @@ but that team does not seem to exist"#,
                         protection.pattern,
                     );
                 }
+                if protection.requires_code_owner_reviews {
+                    bail!(
+                        r#"repo '{}' uses a branch protection for {} that does not require a PR, but sets the `requires-code-owner-reviews` attribute"#,
+                        repo.name,
+                        protection.pattern,
+                    );
+                }
+                if !protection.bypass_actors.is_empty() {
+                    bail!(
+                        r#"repo '{}' uses a branch protection for {} that does not require a PR, but sets the `bypass-actors` attribute"#,
+                        repo.name,
+                        protection.pattern,
+                    );
+                }
             }
 
             let managed_by_homu = protection.merge_bots.contains(&MergeBot::Homu);
@@ -872,12 +2020,15 @@ but that team does not seem to exist"#,
                 }
                 if protection.required_approvals.is_some()
                     || protection.dismiss_stale_review
+                    || protection.requires_code_owner_reviews
                     || !protection.pr_required
                     || !protection.allowed_merge_teams.is_empty()
+                    || !protection.bypass_actors.is_empty()
                 {
                     bail!(
                         r#"repo '{}' uses the homu merge bot, but its branch protection for {} uses invalid
-attributes (`required-approvals`, `dismiss-stale-review`, `pr-required` or `allowed-merge-teams`).
+attributes (`required-approvals`, `dismiss-stale-review`, `requires-code-owner-reviews`,
+`pr-required`, `allowed-merge-teams` or `bypass-actors`).
 Please remove the attributes when using bors"#,
                         repo.name,
                         protection.pattern,
@@ -889,6 +2040,75 @@ Please remove the attributes when using bors"#,
     })
 }
 
+/// Warn about archived repos still configuring bots, as they can't act on
+/// archived repos anyway
+fn validate_archived_repos_config(data: &Data, errors: &mut Vec<String>) {
+    wrapper(data.archived_repos(), errors, |repo, _| {
+        if !repo.bots.is_empty() {
+            bail!(
+                "archived repo '{}/{}' still configures `bots`, which can be removed",
+                repo.org,
+                repo.name,
+            );
+        }
+        Ok(())
+    });
+}
+
+/// Ensure archived repos don't define branch protections or access above read,
+/// as GitHub ignores them on archived repos
+fn validate_archived_repo_branch_protections(data: &Data, errors: &mut Vec<String>) {
+    wrapper(data.archived_repos(), errors, |repo, _| {
+        if !repo.branch_protections.is_empty() {
+            bail!(
+                "archived repo '{}/{}' still defines branch protections, which must be removed",
+                repo.org,
+                repo.name,
+            );
+        }
+        let mut elevated = repo
+            .access
+            .teams
+            .iter()
+            .chain(&repo.access.individuals)
+            .filter(|(_, permission)| **permission != RepoPermission::Read)
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        elevated.sort_unstable();
+        if !elevated.is_empty() {
+            bail!(
+                "archived repo '{}/{}' still grants more than read access to {}, which must be lowered or removed",
+                repo.org,
+                repo.name,
+                elevated.join(", "),
+            );
+        }
+        Ok(())
+    });
+}
+
+/// Warn about branch protections requiring the same CI check more than once
+fn validate_duplicate_ci_checks(data: &Data, errors: &mut Vec<String>) {
+    wrapper(data.all_repos(), errors, |repo, errors| {
+        wrapper(repo.branch_protections.iter(), errors, |protection, _| {
+            let mut seen = HashSet::new();
+            for check in &protection.ci_checks {
+                if !seen.insert(check) {
+                    bail!(
+                        "repo '{}/{}' requires the CI check '{}' multiple times in the branch protection for {}",
+                        repo.org,
+                        repo.name,
+                        check,
+                        protection.pattern,
+                    );
+                }
+            }
+            Ok(())
+        });
+        Ok(())
+    });
+}
+
 /// Enforce that roles are only assigned to a valid team member, and that the
 /// same role id always has a consistent description across teams (because the
 /// role id becomes the Fluent id used for translation).
@@ -0,0 +1,393 @@
+use anyhow::Error;
+use duct::cmd;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn github_team_missing_parent() -> Result<(), Error> {
+    let dir = fixture("github-team-missing-parent")?;
+    edit(
+        &dir.join("teams/foo.toml"),
+        "parent = \"foo\"",
+        "parent = \"missing\"",
+    )?;
+
+    let errors = check(&dir, "validate_github_teams")?;
+    assert_eq!(
+        errors,
+        vec![
+            "the parent of the GitHub team `test-org/renamed-team` is `missing`, \
+             which isn't a GitHub team defined in the `test-org` org"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn github_team_parent_cycle() -> Result<(), Error> {
+    // foo -> renamed-team -> third -> renamed-team
+    let dir = fixture("github-team-parent-cycle")?;
+    let foo = dir.join("teams/foo.toml");
+    edit(
+        &foo,
+        "[[github]]\norgs = [\"test-org\"]\n\n",
+        "[[github]]\norgs = [\"test-org\"]\nparent = \"renamed-team\"\n\n\
+         [[github]]\norgs = [\"test-org\"]\nteam-name = \"third\"\nparent = \"renamed-team\"\n\n",
+    )?;
+    edit(&foo, "parent = \"foo\"", "parent = \"third\"")?;
+
+    let errors = check(&dir, "validate_github_teams")?;
+    assert_eq!(
+        errors,
+        vec![
+            "the GitHub team `test-org/renamed-team` is nested under itself \
+             (renamed-team -> third -> renamed-team)"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn repo_without_admin() -> Result<(), Error> {
+    let dir = fixture("repo-without-admin")?;
+    let repo = dir.join("repos/test-org/some_repo.toml");
+    edit(&repo, "foo = \"admin\"", "foo = \"write\"")?;
+
+    let warnings = check_warnings(&dir, "validate_repo_admins")?;
+    assert_eq!(
+        warnings,
+        vec![
+            "repo 'test-org/some_repo' has no team or individual with admin access; \
+             set `org-owners-admin-only = true` if this is intentional"
+        ]
+    );
+
+    edit(
+        &repo,
+        "bots = []",
+        "bots = []\norg-owners-admin-only = true",
+    )?;
+    assert!(check_warnings(&dir, "validate_repo_admins")?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn allowed_merge_team_without_write_access() -> Result<(), Error> {
+    let dir = fixture("allowed-merge-team-without-write-access")?;
+    edit(
+        &dir.join("repos/test-org/some_repo.toml"),
+        "allowed-merge-teams = [\"foo\"]",
+        "allowed-merge-teams = [\"renamed-team\"]",
+    )?;
+
+    let errors = check(&dir, "validate_branch_protections")?;
+    assert_eq!(
+        errors,
+        vec![
+            "repo 'some_repo' uses a branch protection for master that allows the 'renamed-team' \
+             github team to merge;\nbut that team doesn't have at least write access to the repo"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn team_inclusion_cycle() -> Result<(), Error> {
+    let dir = fixture("team-inclusion-cycle")?;
+    edit(
+        &dir.join("teams/foo.toml"),
+        "[people]\n",
+        "[people]\nincluded-teams = [\"wg-test\"]\n",
+    )?;
+    edit(
+        &dir.join("teams/wg-test.toml"),
+        "[people]\n",
+        "[people]\nincluded-teams = [\"foo\"]\n",
+    )?;
+    let expected = vec!["team `foo` includes its own members: foo => wg-test => foo"];
+
+    assert_eq!(check(&dir, "validate_member_inclusions")?, expected);
+
+    // Checking a single person must not loop forever either.
+    let output = cmd!(env!("CARGO_BIN_EXE_rust-team"), "check-person", "user-0")
+        .dir(&dir)
+        .env("RUST_BACKTRACE", "0")
+        .stderr_capture()
+        .unchecked()
+        .run()?;
+    assert!(!output.status.success());
+    assert_eq!(
+        messages(&String::from_utf8(output.stderr)?, "validation error: "),
+        expected
+    );
+    Ok(())
+}
+
+#[test]
+fn check_person_only_reports_that_person() -> Result<(), Error> {
+    let dir = fixture("check-person-only-reports-that-person")?;
+    // The message mentions user-0, but concerns user-3.
+    edit(
+        &dir.join("people/user-3.toml"),
+        "email = \"user3@example.com\"",
+        "email = \"user-0\"",
+    )?;
+    let check_person = |name: &str| {
+        cmd!(env!("CARGO_BIN_EXE_rust-team"), "check-person", name)
+            .dir(&dir)
+            .env("RUST_BACKTRACE", "0")
+            .env_remove("GITHUB_TOKEN")
+            .stderr_capture()
+            .unchecked()
+            .run()
+    };
+
+    assert!(check_person("user-0")?.status.success());
+
+    let output = check_person("user-3")?;
+    assert!(!output.status.success());
+    assert_eq!(
+        messages(&String::from_utf8(output.stderr)?, "validation error: "),
+        vec!["invalid email address of `user-3`: user-0"]
+    );
+    Ok(())
+}
+
+#[test]
+fn ci_contexts_skip_unmanaged_orgs() -> Result<(), Error> {
+    let dir = fixture("ci-contexts-skip-unmanaged-orgs")?;
+    let repo = dir.join("repos/test-org/some_repo.toml");
+    edit(&repo, "org = \"test-org\"", "org = \"other-org\"")?;
+    std::fs::create_dir_all(dir.join("repos/other-org"))?;
+    std::fs::rename(&repo, dir.join("repos/other-org/some_repo.toml"))?;
+
+    // Any request to the (unreachable) API would be reported as a warning.
+    let output = cmd!(
+        env!("CARGO_BIN_EXE_rust-team"),
+        "check",
+        "--only",
+        "validate_branch_protection_ci_contexts"
+    )
+    .dir(&dir)
+    .env("RUST_BACKTRACE", "0")
+    .env("GITHUB_TOKEN", "token")
+    .env("GITHUB_API_BASE_URL", "http://127.0.0.1:1/")
+    .stderr_capture()
+    .unchecked()
+    .run()?;
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(
+        output.status.success(),
+        "the check unexpectedly failed:\n{}",
+        stderr
+    );
+    assert!(messages(&stderr, "validation warning: ").is_empty());
+    Ok(())
+}
+
+#[test]
+fn invalid_repo_settings() -> Result<(), Error> {
+    let dir = fixture("invalid-repo-settings")?;
+    let repo = dir.join("repos/test-org/some_repo.toml");
+    edit(
+        &repo,
+        "topics = [\"rust\", \"test-data\"]",
+        "topics = [\"Rust\", \"-test-data\"]",
+    )?;
+    edit(
+        &repo,
+        "allow-merge-commit = false",
+        "allow-merge-commit = false\nallow-rebase-merge = false",
+    )?;
+    edit(
+        &repo,
+        "allow-squash-merge = true",
+        "allow-squash-merge = false",
+    )?;
+
+    // Every invalid topic is reported, not only the first one.
+    let errors = check(&dir, "validate_repo_topics")?;
+    assert_eq!(errors.len(), 2);
+    assert!(
+        errors[0].starts_with("The topic '-test-data' of the repo test-org/some_repo is invalid")
+    );
+    assert!(errors[1].starts_with("The topic 'Rust' of the repo test-org/some_repo is invalid"));
+
+    assert_eq!(
+        check(&dir, "validate_repo_merge_methods")?,
+        vec![
+            "The repo test-org/some_repo disallows all merge methods, \
+             but GitHub requires at least one to be allowed"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn marker_team_with_side_effects() -> Result<(), Error> {
+    let dir = fixture("marker-team-with-side-effects")?;
+    edit(
+        &dir.join("teams/foo.toml"),
+        "top-level = true",
+        "top-level = true\nkind = \"marker-team\"",
+    )?;
+
+    let warnings = check_warnings(&dir, "validate_marker_teams")?;
+    assert_eq!(
+        warnings,
+        vec![
+            "marker team 'foo' has `github` teams, `lists`, `permissions`, repo access, \
+             but marker teams are labels and shouldn't have side effects"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn check_file() -> Result<(), Error> {
+    let dir = fixture("check-file")?;
+    assert_eq!(
+        run_check_file(&dir, "people/user-3.toml")?,
+        (true, Vec::new())
+    );
+    assert_eq!(run_check_file(&dir, "teams/foo.toml")?, (true, Vec::new()));
+
+    edit(
+        &dir.join("people/user-3.toml"),
+        "email = \"user3@example.com\"",
+        "email = true",
+    )?;
+    assert_eq!(
+        run_check_file(&dir, "people/user-3.toml")?,
+        (
+            false,
+            vec!["`email = true` is not valid (for person user-3)".to_string()]
+        )
+    );
+
+    // Archived teams are checked like the other teams.
+    let archived = dir.join("teams/archive/wg-test.toml");
+    std::fs::rename(dir.join("teams/wg-test.toml"), &archived)?;
+    edit(&archived, "name = \"wg-test\"", "name = \"test\"")?;
+    assert_eq!(
+        run_check_file(&dir, "teams/archive/wg-test.toml")?,
+        (
+            false,
+            vec!["working group `test`'s name doesn't start with `wg-`".to_string()]
+        )
+    );
+
+    // Only the `archive` directory of the teams contains team files.
+    std::fs::copy(
+        dir.join("teams/foo.toml"),
+        dir.join("repos/archive/foo.toml"),
+    )?;
+    let output = cmd!(
+        env!("CARGO_BIN_EXE_rust-team"),
+        "check-file",
+        "repos/archive/foo.toml"
+    )
+    .dir(&dir)
+    .env("RUST_BACKTRACE", "0")
+    .stderr_capture()
+    .unchecked()
+    .run()?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains(
+        "repos/archive/foo.toml is not a person or team file \
+         (in the `people`, `teams` or `teams/archive` directory)"
+    ));
+    Ok(())
+}
+
+/// Copy the valid data used by the static API test into a new directory.
+fn fixture(name: &str) -> Result<PathBuf, Error> {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("static-api");
+    let dest = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    if dest.exists() {
+        std::fs::remove_dir_all(&dest)?;
+    }
+    for entry in walkdir::WalkDir::new(&src) {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(&src)?;
+        if relative.starts_with("_expected") || relative.starts_with("_output") {
+            continue;
+        }
+        if entry.file_type().is_dir() {
+            std::fs::create_dir_all(dest.join(relative))?;
+        } else {
+            std::fs::copy(entry.path(), dest.join(relative))?;
+        }
+    }
+    Ok(dest)
+}
+
+fn edit(path: &Path, from: &str, to: &str) -> Result<(), Error> {
+    let content = std::fs::read_to_string(path)?;
+    assert!(
+        content.contains(from),
+        "{} doesn't contain {from:?}",
+        path.display()
+    );
+    std::fs::write(path, content.replacen(from, to, 1))?;
+    Ok(())
+}
+
+/// Run a single check, returning the validation errors it reported.
+fn check(dir: &Path, name: &str) -> Result<Vec<String>, Error> {
+    let (success, output) = run_check(dir, name)?;
+    assert!(!success, "the check unexpectedly passed");
+    Ok(messages(&output, "validation error: "))
+}
+
+/// Run a single check which must pass, returning the validation warnings it reported.
+fn check_warnings(dir: &Path, name: &str) -> Result<Vec<String>, Error> {
+    let (success, output) = run_check(dir, name)?;
+    assert!(success, "the check unexpectedly failed:\n{}", output);
+    Ok(messages(&output, "validation warning: "))
+}
+
+fn run_check(dir: &Path, name: &str) -> Result<(bool, String), Error> {
+    let output = cmd!(env!("CARGO_BIN_EXE_rust-team"), "check", "--only", name)
+        .dir(dir)
+        .env("RUST_BACKTRACE", "0")
+        .stderr_capture()
+        .unchecked()
+        .run()?;
+    Ok((output.status.success(), String::from_utf8(output.stderr)?))
+}
+
+/// Run `check-file` on a file, returning whether it passed and the validation errors.
+fn run_check_file(dir: &Path, file: &str) -> Result<(bool, Vec<String>), Error> {
+    let output = cmd!(env!("CARGO_BIN_EXE_rust-team"), "check-file", file)
+        .dir(dir)
+        .env("RUST_BACKTRACE", "0")
+        .stderr_capture()
+        .unchecked()
+        .run()?;
+    Ok((
+        output.status.success(),
+        messages(&String::from_utf8(output.stderr)?, "validation error: "),
+    ))
+}
+
+/// Extract the messages with the given prefix from the log, including the
+/// (indented) continuation lines of multi-line messages.
+fn messages(output: &str, prefix: &str) -> Vec<String> {
+    let mut messages: Vec<String> = Vec::new();
+    let mut in_message = false;
+    for line in output.lines() {
+        if let Some(message) = line.split(prefix).nth(1) {
+            messages.push(message.to_string());
+            in_message = true;
+        } else if let (true, Some(continuation)) = (in_message, line.strip_prefix("    ")) {
+            let last = messages.last_mut().unwrap();
+            last.push('\n');
+            last.push_str(continuation);
+        } else {
+            in_message = false;
+        }
+    }
+    messages
+}
@@ -0,0 +1,35 @@
+use anyhow::Error;
+use duct::cmd;
+use std::path::Path;
+
+#[test]
+fn person_diff_from_empty_snapshot() -> Result<(), Error> {
+    let empty = Path::new(env!("CARGO_TARGET_TMPDIR")).join("person-diff-empty");
+    std::fs::create_dir_all(empty.join("v1").join("permissions"))?;
+    std::fs::write(empty.join("v1").join("teams.json"), "{}")?;
+    std::fs::write(empty.join("v1").join("repos.json"), "{}")?;
+    std::fs::write(empty.join("v1").join("people.json"), r#"{"people": {}}"#)?;
+    let expected = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("static-api")
+        .join("_expected");
+
+    // The access to the repo is granted through a GitHub team named
+    // differently than the team user-2 is a member of.
+    let output = cmd!(
+        env!("CARGO_BIN_EXE_rust-team"),
+        "person-diff",
+        "user-2",
+        &empty,
+        &expected
+    )
+    .read()?;
+    assert_eq!(
+        output,
+        "teams:\n  + wg-test\n\n\
+         permissions:\n  + bors.crater.try\n  + bors.crates_io.review\n  \
+         + bors.crates_io.try\n  + crater\n\n\
+         repos:\n  + test-org/some_repo (read via renamed-team)"
+    );
+    Ok(())
+}
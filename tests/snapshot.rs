@@ -56,7 +56,7 @@ fn static_api() -> Result<(), Error> {
                 ansi_term::Color::Red.bold().paint("!!! the file"),
                 ansi_term::Color::White
                     .bold()
-                    .paint(&file.to_str().unwrap().to_string()),
+                    .paint(file.to_str().unwrap().to_string()),
                 ansi_term::Color::Red.bold().paint("does not match"),
             );
             println!("{}", changeset);
@@ -77,6 +77,66 @@ fn static_api() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn static_api_incremental() -> Result<(), Error> {
+    let dest = Path::new(env!("CARGO_TARGET_TMPDIR")).join("static-api-incremental");
+    if dest.exists() {
+        std::fs::remove_dir_all(&dest)?;
+    }
+
+    step("generating the full static api contents");
+    cmd!(bin(), "static-api", &dest)
+        .dir(dir_valid())
+        .assert_success()?;
+    let full = list_files(&dest)?;
+
+    step("regenerating without changes");
+    let output = cmd!(bin(), "static-api", &dest, "--incremental")
+        .dir(dir_valid())
+        .read()?;
+    assert_eq!(output, "");
+    assert_eq!(list_files(&dest)?, full);
+
+    step("regenerating with a changed, a removed and a stale file");
+    let teams = dest.join("v1").join("teams.json");
+    let teams_content = std::fs::read_to_string(&teams)?;
+    std::fs::write(&teams, "{}")?;
+    std::fs::remove_file(dest.join("v1").join("people.json"))?;
+    std::fs::write(dest.join("v1").join("stale.json"), "{}")?;
+    let output = cmd!(bin(), "static-api", &dest, "--incremental")
+        .dir(dir_valid())
+        .read()?;
+    assert_eq!(output, "v1/people.json\nv1/stale.json\nv1/teams.json");
+    assert_eq!(list_files(&dest)?, full);
+    assert_eq!(std::fs::read_to_string(&teams)?, teams_content);
+
+    step("regenerating with a filter");
+    cmd!(
+        bin(),
+        "static-api",
+        &dest,
+        "--incremental",
+        "--filter",
+        "foo"
+    )
+    .dir(dir_valid())
+    .assert_success()?;
+    assert_eq!(list_files(&dest)?, full);
+
+    Ok(())
+}
+
+fn list_files(dir: &Path) -> Result<HashSet<PathBuf>, Error> {
+    let mut files = HashSet::new();
+    for entry in walkdir::WalkDir::new(dir) {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            files.insert(entry.path().strip_prefix(dir)?.to_path_buf());
+        }
+    }
+    Ok(files)
+}
+
 fn bin() -> &'static str {
     env!("CARGO_BIN_EXE_rust-team")
 }
@@ -92,7 +152,7 @@ fn step(name: &str) {
         "{}",
         ansi_term::Color::White
             .bold()
-            .paint(&format!("==> {}", name))
+            .paint(format!("==> {}", name))
     );
 }
 